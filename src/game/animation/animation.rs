@@ -2,11 +2,10 @@ use bevy::prelude::*;
 
 use crate::game::{
     common::components::characters::char_state::State,
-    enemies::enemies::{EnemyType},
+    enemies::registry::{EnemyKind, EnemyRegistry},
     enemies::component::Enemy,
     game_state::GameState,
-    player::{component::Player, weapon::Weapon},
-    resources::{CursorPosition, GlobalTextureAtlas},
+    player::{class::PlayerAnimation, component::Player, death::Dying, weapon::Weapon},
 };
 
 #[derive(Component, Default)]
@@ -39,92 +38,54 @@ fn animation_timer_tick(time: Res<Time>, mut query: Query<&mut AnimationTimer>)
 }
 
 fn animate_player(
-    mut player_query: Query<(&mut Sprite, &mut AtlasIndex, &State, &AnimationTimer), With<Player>>,
-    atlas: Res<GlobalTextureAtlas>,
+    mut player_query: Query<(&mut Sprite, &mut AtlasIndex, &State, &AnimationTimer, &PlayerAnimation), (With<Player>, Without<Dying>)>,
 ) {
     if player_query.is_empty() {
         return;
     }
-    if let Ok((mut sprite, mut aindex, state, timer)) = player_query.single_mut() {
+    if let Ok((mut sprite, mut aindex, state, timer, frames)) = player_query.single_mut() {
         if timer.0.just_finished() {
-            match state {
+            // the class may use its own sheet, so only the atlas index changes
+            let new_index = match state {
                 State::Idle => {
-                    aindex.0 = 2;
-                    let new_index = 2;
-                    let prev_fx = sprite.flip_x;
-                    let prev_fy = sprite.flip_y;
-                    sprite.clone_from(&Sprite::from_atlas_image(
-                        atlas.image.clone(),
-                        TextureAtlas {
-                            layout: atlas.layout.clone(),
-                            index: new_index,
-                        },
-                    ));
-                    sprite.flip_x = prev_fx;
-                    sprite.flip_y = prev_fy;
+                    aindex.0 = 0;
+                    frames.idle
                 }
+                State::Moving if frames.moving.is_empty() => frames.idle,
                 State::Moving => {
-                    aindex.0 = (aindex.0 + 1) % 4;
-                    let new_index = 4 + aindex.0;
-                    let prev_fx = sprite.flip_x;
-                    let prev_fy = sprite.flip_y;
-                    sprite.clone_from(&Sprite::from_atlas_image(
-                        atlas.image.clone(),
-                        TextureAtlas {
-                            layout: atlas.layout.clone(),
-                            index: new_index,
-                        },
-                    ));
-                    sprite.flip_x = prev_fx;
-                    sprite.flip_y = prev_fy;
+                    aindex.0 = (aindex.0 + 1) % frames.moving.len();
+                    frames.moving[aindex.0]
                 }
+            };
+            if let Some(atlas) = sprite.texture_atlas.as_mut() {
+                atlas.index = new_index;
             }
         }
     }
 }
 
 fn animate_enemy(
-    mut enemy_query: Query<(&mut Sprite, &mut AtlasIndex, &AnimationTimer, &EnemyType), With<Enemy>>,
-    atlas_res: Res<GlobalTextureAtlas>,
+    mut enemy_query: Query<(&mut Sprite, &mut AtlasIndex, &AnimationTimer, &EnemyKind), With<Enemy>>,
+    registry: Option<Res<EnemyRegistry>>,
 ) {
-    if enemy_query.is_empty() {
-        return;
-    }
+    let Some(registry) = registry else { return };
 
-        for (mut sprite, mut aindex, timer, enemy_type) in enemy_query.iter_mut() {
-            if timer.0.just_finished() {
-                aindex.0 = (aindex.0 + 1) % 4;
-                let new_index = enemy_type.get_base_sprite_index() + aindex.0;
-                let prev_flip_x = sprite.flip_x;
-                let prev_flip_y = sprite.flip_y;
-                sprite.clone_from(&Sprite::from_atlas_image(
-                    atlas_res.image.clone(),
-                    TextureAtlas {
-                        layout: atlas_res.layout.clone(),
-                        index: new_index,
-                    },
-                ));
-                sprite.flip_x = prev_flip_x;
-                sprite.flip_y = prev_flip_y;
+    for (mut sprite, mut aindex, timer, kind) in enemy_query.iter_mut() {
+        if timer.0.just_finished() {
+            // frames come from the enemy's spec, see `EnemyRegistry::frames`
+            let frames = registry.frames(*kind);
+            aindex.0 = (aindex.0 + 1) % frames.len();
+            if let Some(atlas) = sprite.texture_atlas.as_mut() {
+                atlas.index = frames[aindex.0];
             }
         }
-}
-
-fn flip_gun_sprite_y(
-    cursor_position: Res<CursorPosition>,
-    mut gun_query: Query<(&mut Sprite, &Transform), With<Weapon>>,
-) {
-    if gun_query.is_empty() {
-        return;
     }
+}
 
-    if let Ok((mut sprite, transform)) = gun_query.single_mut() {
-        if let Some(cursor_position) = cursor_position.0 {
-            if cursor_position.x > transform.translation.x {
-                sprite.flip_y = false;
-            } else {
-                sprite.flip_y = true;
-            }
-        }
+// weapons are rotated towards where they fire; flip them when pointing left
+// so they don't render upside down
+fn flip_gun_sprite_y(mut gun_query: Query<(&mut Sprite, &Transform), With<Weapon>>) {
+    for (mut sprite, transform) in gun_query.iter_mut() {
+        sprite.flip_y = transform.local_x().x < 0.0;
     }
 }
@@ -1,7 +1,14 @@
 use bevy::prelude::*;
 use bevy_asset_loader::prelude::*;
 
+use crate::game::enemies::affixes::AffixList;
+use crate::game::enemies::boss::BossList;
+use crate::game::enemies::director::WaveTimeline;
 use crate::game::enemies::enemies_example::EnemyList;
+use crate::game::game_state::GameState;
+use crate::game::player::class::ClassList;
+use crate::game::progression::upgrades::UpgradeList;
+use crate::game::player::weapon_spec::WeaponList;
 
 #[derive(AssetCollection, Resource)]
 pub struct GameAssets {
@@ -10,4 +17,35 @@ pub struct GameAssets {
 
     #[asset(path = "entities/enemies/devil.ron")]
     pub enemy_list: Handle<EnemyList>,
+
+    #[asset(path = "entities/enemies/affixes.ron")]
+    pub affix_list: Handle<AffixList>,
+
+    #[asset(path = "entities/bosses/bosses.ron")]
+    pub boss_list: Handle<BossList>,
+
+    #[asset(path = "waves/timeline.ron")]
+    pub wave_timeline: Handle<WaveTimeline>,
+
+    #[asset(path = "classes/classes.ron")]
+    pub class_list: Handle<ClassList>,
+
+    #[asset(path = "weapons/weapons.ron")]
+    pub weapon_list: Handle<WeaponList>,
+
+    #[asset(path = "upgrades/upgrades.ron")]
+    pub upgrade_list: Handle<UpgradeList>,
+}
+
+pub struct GameAssetsPlugin;
+
+impl Plugin for GameAssetsPlugin {
+    fn build(&self, app: &mut App) {
+        // data files must be loaded before anything spawns from them
+        app.add_loading_state(
+            LoadingState::new(GameState::Loading)
+                .continue_to_state(GameState::InGame)
+                .load_collection::<GameAssets>(),
+        );
+    }
 }
@@ -3,13 +3,29 @@ use std::time::Duration;
 use bevy::{prelude::*, time::common_conditions::on_timer};
 
 use crate::game::player::component::Player;
-use crate::game::enemies::component::Enemy;
+use crate::game::enemies::component::{Enemy, AttackCooldown, Knockback};
 use crate::game::common::components::characters::health::Health;
-use crate::game::player::{weapon::Bullet, events::PlayerDamagedEvent};
+use crate::game::common::components::characters::invulnerability::Invulnerable;
+use crate::game::common::components::characters::stats::EffectiveStats;
+use crate::game::player::death::Dying;
+use crate::game::player::{
+    weapon::{Bullet, BulletDamage, BulletDirection, HitEnemies, MeleeSwing, ProjectileEffects},
+    weapon_spec::MeleeShape,
+    explosion::ExplosionEvent,
+    events::PlayerDamagedEvent,
+};
 use crate::game::game_state::GameState;
 use crate::game::config as cfg;
 use crate::game::spatial::{KDTree2, Collidable};
 use crate::game::enemies::enemies::CollidableEnemy;
+use crate::game::enemies::behaviour::EnemyProjectile;
+use crate::game::enemies::affixes::Elite;
+use crate::game::run::RunStats;
+
+// distance at which a bullet counts as touching an enemy
+const BULLET_HIT_RANGE: f32 = 20.0;
+// distance at which an enemy projectile counts as touching the player
+const ENEMY_PROJECTILE_HIT_RANGE: f32 = 12.0;
 
 pub struct CollisionPlugin;
 
@@ -22,16 +38,23 @@ impl Plugin for CollisionPlugin {
                     update_enemy_kd_tree
                         .run_if(on_timer(Duration::from_secs_f32(cfg::KD_TREE_REFRESH_RATE))),
                     handle_enemy_bullet_collision,
+                    resolve_melee_swings,
                     handle_enemy_player_collision,
+                    handle_enemy_projectile_collision,
                 )
                     .run_if(in_state(GameState::InGame)),
             );
     }
 }
 
+// Contact damage is gated twice so it doesn't scale with frame rate: each
+// enemy can only hit once per 1 / attack_speed seconds, and the player ignores
+// further hits while invulnerable after taking one.
 fn handle_enemy_player_collision(
     mut commands: Commands,
-    player_query: Query<&Transform, With<Player>>,
+    time: Res<Time>,
+    player_query: Query<&Transform, (With<Player>, Without<Invulnerable>, Without<Dying>)>,
+    mut enemy_query: Query<(&EffectiveStats, &mut AttackCooldown, &mut Health, Option<&Elite>), With<Enemy>>,
     tree: Res<KDTree2>,
 ) {
     if player_query.is_empty() {
@@ -42,40 +65,197 @@ fn handle_enemy_player_collision(
         Err(_) => return,
     };
 
-    if let Some((pos, _entity)) = tree.nearest_neighbour(player_pos) {
-        if pos.distance(player_pos) <= 20.0 {
-            // trigger a PlayerDamagedEvent immediately on the World
-            commands.trigger(PlayerDamagedEvent { damage: cfg::ENEMY_DAMAGE });
+    let now = time.elapsed_secs_f64();
+    for (_pos, entity) in tree.within_distance(player_pos, cfg::ENEMY_CONTACT_RANGE) {
+        let Some(e) = entity else { continue };
+        let Ok((stats, mut cooldown, mut health, elite)) = enemy_query.get_mut(e) else { continue };
+        if !cooldown.is_ready(now) {
+            continue;
+        }
+
+        cooldown.trigger(now, stats.attack_speed);
+        // trigger a PlayerDamagedEvent immediately on the World
+        commands.trigger(PlayerDamagedEvent { damage: stats.damage });
+        if let Some(elite) = elite {
+            health.heal(stats.damage * elite.heal_fraction());
+        }
+        // the hit makes the player invulnerable, so the rest can't land this frame
+        break;
+    }
+}
+
+// Enemy projectiles fly through the player while invulnerable and are used up
+// by the hit that lands.
+fn handle_enemy_projectile_collision(
+    mut commands: Commands,
+    player_query: Query<&Transform, (With<Player>, Without<Invulnerable>, Without<Dying>)>,
+    projectile_query: Query<(Entity, &Transform, &EnemyProjectile), Without<Player>>,
+) {
+    let Ok(player_tf) = player_query.single() else { return };
+    let player_pos = player_tf.translation.truncate();
+
+    for (entity, transform, projectile) in projectile_query.iter() {
+        if transform.translation.truncate().distance(player_pos) > ENEMY_PROJECTILE_HIT_RANGE {
+            continue;
         }
+        commands.trigger(PlayerDamagedEvent { damage: projectile.damage });
+        commands.entity(entity).despawn();
+        break;
     }
 }
 
-// AutomaticUpdate plugin maintains the KDTree resource; no manual update needed.
+// Bullets hit the closest living enemy in range that they haven't hit yet, then
+// pierce, bounce or stop depending on their remaining `ProjectileEffects`.
 fn handle_enemy_bullet_collision(
     mut commands: Commands,
-    bullet_query: Query<(Entity, &Transform), With<Bullet>>,
+    mut bullet_query: Query<
+        (Entity, &Transform, &BulletDamage, &mut BulletDirection, &mut ProjectileEffects, &mut HitEnemies),
+        With<Bullet>,
+    >,
     tree: Res<KDTree2>,
-    mut enemy_query: Query<(&Transform, &mut Health), With<Enemy>>,
+    mut enemy_query: Query<&mut Health, With<Enemy>>,
+    mut run_stats: ResMut<RunStats>,
 ) {
     if bullet_query.is_empty() || enemy_query.is_empty() {
         return;
     }
-    for (b_entity, b_t) in bullet_query.iter() {
+    for (b_entity, b_t, damage, mut direction, mut effects, mut hits) in bullet_query.iter_mut() {
+        // fused explosives fly through enemies until they expire
+        if effects.explosion.is_some_and(|x| !x.on_impact) {
+            continue;
+        }
         let bullet_pos = b_t.translation;
-        if let Some((nearest_pos, entity)) = tree.nearest_neighbour(bullet_pos) {
-            if nearest_pos.distance(bullet_pos) <= 20.0 {
-                if let Some(e) = entity {
-                    if let Ok((_, mut health)) = enemy_query.get_mut(e) {
-                        health.take_damage(cfg::BULLET_DAMAGE);
-                        // remove bullet so it doesn't hit again
-                        commands.entity(b_entity).despawn();
-                    }
+        let Some((enemy_pos, enemy)) = nearest_unhit(&tree, &enemy_query, bullet_pos, BULLET_HIT_RANGE, &hits.0) else {
+            continue;
+        };
+
+        // impact explosives deal their damage through the blast only
+        if let Some(explosion) = effects.explosion {
+            commands.trigger(ExplosionEvent { position: bullet_pos, damage: damage.0, explosion });
+            commands.entity(b_entity).despawn();
+            continue;
+        }
+
+        let Ok(mut health) = enemy_query.get_mut(enemy) else { continue };
+        run_stats.damage_dealt += health.take_damage(damage.0);
+        let survived = !health.is_dead();
+        hits.0.push(enemy);
+
+        // the enemy may be despawned this frame, hence try_insert
+        if effects.knockback > 0.0 && survived {
+            let push = direction.0.truncate().normalize_or_zero() * effects.knockback;
+            commands.entity(enemy).try_insert(Knockback(push));
+        }
+        if effects.chain_jumps > 0 {
+            run_stats.damage_dealt += chain_lightning(&tree, &mut enemy_query, enemy, enemy_pos, &effects, damage.0);
+        }
+
+        if effects.pierce > 0 {
+            effects.pierce -= 1;
+            continue;
+        }
+        if effects.bounces > 0 {
+            effects.bounces -= 1;
+            if let Some((next_pos, _)) = nearest_unhit(&tree, &enemy_query, enemy_pos, effects.bounce_range, &hits.0) {
+                direction.0 = (next_pos.truncate() - bullet_pos.truncate()).normalize_or_zero().extend(0.0);
+                continue;
+            }
+        }
+        commands.entity(b_entity).despawn();
+    }
+}
+
+// Melee hits land once, when the swing appears: every living enemy inside the
+// shape takes damage a single time, however long the sweep is shown.
+fn resolve_melee_swings(
+    mut commands: Commands,
+    swing_query: Query<&MeleeSwing, Added<MeleeSwing>>,
+    tree: Res<KDTree2>,
+    mut enemy_query: Query<&mut Health, With<Enemy>>,
+    mut run_stats: ResMut<RunStats>,
+) {
+    for swing in swing_query.iter() {
+        let dir = swing.direction.normalize_or(Vec2::X);
+        let reach = swing.spec.reach;
+        let half_width = swing.spec.width * 0.5;
+        let half_arc = swing.spec.arc_degrees.to_radians() * 0.5;
+
+        // whips query a circle around the middle of the line, the rest around the origin
+        let (center, radius) = match swing.spec.shape {
+            MeleeShape::Whip => (swing.origin + dir * reach * 0.5, Vec2::new(reach * 0.5, half_width).length()),
+            MeleeShape::Arc | MeleeShape::Spin => (swing.origin, reach),
+        };
+
+        for (pos, entity) in tree.within_distance(center.extend(0.0), radius) {
+            let Some(enemy) = entity else { continue };
+            let offset = pos.truncate() - swing.origin;
+            let inside = match swing.spec.shape {
+                MeleeShape::Spin => true,
+                MeleeShape::Arc => offset.length_squared() < 1.0 || dir.angle_to(offset).abs() <= half_arc,
+                MeleeShape::Whip => {
+                    let along = offset.dot(dir);
+                    (0.0..=reach).contains(&along) && offset.perp_dot(dir).abs() <= half_width
                 }
+            };
+            if !inside {
+                continue;
+            }
+            let Ok(mut health) = enemy_query.get_mut(enemy) else { continue };
+            if health.is_dead() {
+                continue;
+            }
+            run_stats.damage_dealt += health.take_damage(swing.damage);
+
+            if swing.knockback > 0.0 && !health.is_dead() {
+                let push = offset.normalize_or(dir) * swing.knockback;
+                commands.entity(enemy).try_insert(Knockback(push));
             }
         }
     }
 }
 
+// Arcs from `first` to the nearest enemy not struck yet, `chain_jumps` times,
+// scaling the damage down on every jump. Returns the total damage dealt.
+fn chain_lightning(
+    tree: &KDTree2,
+    enemy_query: &mut Query<&mut Health, With<Enemy>>,
+    first: Entity,
+    from: Vec3,
+    effects: &ProjectileEffects,
+    damage: f32,
+) -> f32 {
+    let mut struck = vec![first];
+    let mut pos = from;
+    let mut damage = damage;
+    let mut dealt = 0.0;
+    for _ in 0..effects.chain_jumps {
+        damage *= effects.chain_damage;
+        let Some((next_pos, next)) = nearest_unhit(tree, enemy_query, pos, effects.chain_range, &struck) else { break };
+        if let Ok(mut health) = enemy_query.get_mut(next) {
+            dealt += health.take_damage(damage);
+        }
+        struck.push(next);
+        pos = next_pos;
+    }
+    dealt
+}
+
+// closest living enemy within `range` of `pos`, ignoring `exclude`
+fn nearest_unhit(
+    tree: &KDTree2,
+    enemy_query: &Query<&mut Health, With<Enemy>>,
+    pos: Vec3,
+    range: f32,
+    exclude: &[Entity],
+) -> Option<(Vec3, Entity)> {
+    let pos2 = pos.truncate();
+    tree.within_distance(pos, range)
+        .into_iter()
+        .filter_map(|(p, e)| e.map(|e| (p, e)))
+        .filter(|(_, e)| !exclude.contains(e) && enemy_query.get(*e).is_ok_and(|h| !h.is_dead()))
+        .min_by(|(a, _), (b, _)| a.truncate().distance_squared(pos2).total_cmp(&b.truncate().distance_squared(pos2)))
+}
+
 fn update_enemy_kd_tree(
     mut tree: ResMut<KDTree2>,
     enemy_query: Query<(&Transform, Entity), With<CollidableEnemy>>,
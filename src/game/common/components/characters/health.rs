@@ -1,8 +1,14 @@
 use bevy::prelude::*;
 
 use crate::game::player::component::Player;
+use crate::game::player::death::Dying;
+use crate::game::player::events::PlayerDiedEvent;
 use crate::game::player::weapon::Weapon;
+use crate::game::enemies::component::Enemy;
+use crate::game::enemies::events::EnemyKilledEvent;
 use crate::game::game_state::GameState;
+use crate::game::run::RunStats;
+use crate::game::common::components::characters::invulnerability::tick_invulnerability;
 
 #[derive(Component)]
 pub struct Health {
@@ -28,16 +34,16 @@ impl Health {
         self.current <= 0.0
     }
 
-    pub fn take_damage(&mut self, damage: f32) {
+    // returns the damage actually applied (overkill is not counted)
+    pub fn take_damage(&mut self, damage: f32) -> f32 {
         if self.current <= 0.0 {
-            return;
+            return 0.0;
         }
 
-        self.current -= damage;
-        if self.current < 0.0 {
-            self.current = 0.0;
-        }
+        let applied = damage.min(self.current);
+        self.current -= applied;
         info!("Entity took {} damage, current health: {}/{}", damage, self.current, self.max);
+        applied
     }
 
     pub fn heal(&mut self, amount: f32) {
@@ -54,29 +60,41 @@ impl Plugin for HealthPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
             Update,
-            despawn_dead_entities.run_if(in_state(GameState::InGame)),
+            (despawn_dead_entities, tick_invulnerability).run_if(in_state(GameState::InGame)),
         );
     }
 }
 
 fn despawn_dead_entities(
     mut commands: Commands,
-    query: Query<(Entity, &Health, Option<&Player>)>,
+    mut run_stats: ResMut<RunStats>,
+    query: Query<(Entity, &Health, &Transform, Option<&Player>, Option<&Enemy>), Without<Dying>>,
     weapon_query: Query<Entity, With<Weapon>>,
 ) {
     // Loop por todas entidades com componente Health
-    for (entity, health, maybe_player) in query.iter() {
+    for (entity, health, transform, maybe_player, maybe_enemy) in query.iter() {
         // se health <= 0
-        if health.is_dead() {
-            info!("Despawning entity {:?} with zero health", entity);
-            // se for um player, despawna tbm suas armas
-            if maybe_player.is_some() {
-                for weapon_entity in weapon_query.iter() {
-                    commands.entity(weapon_entity).despawn();
-                }
+        if !health.is_dead() {
+            continue;
+        }
+
+        // o player nao some: despawna suas armas e comeca a sequencia de morte
+        if maybe_player.is_some() {
+            info!("Player {:?} died", entity);
+            for weapon_entity in weapon_query.iter() {
+                commands.entity(weapon_entity).despawn();
             }
+            commands.entity(entity).insert(Dying::new());
+            commands.trigger(PlayerDiedEvent { entity });
+            continue;
+        }
 
-            commands.entity(entity).despawn();
+        if maybe_enemy.is_some() {
+            run_stats.kills += 1;
+            commands.trigger(EnemyKilledEvent { entity, position: transform.translation });
         }
+
+        info!("Despawning entity {:?} with zero health", entity);
+        commands.entity(entity).despawn();
     }
 }
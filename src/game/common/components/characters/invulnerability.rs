@@ -0,0 +1,37 @@
+use bevy::prelude::*;
+
+use crate::game::player::death::Dying;
+
+// Damage immunity window granted after taking a hit. While present, incoming
+// damage is ignored; the component removes itself when the timer runs out.
+#[derive(Component)]
+pub struct Invulnerable(pub Timer);
+
+impl Invulnerable {
+    pub fn from_seconds(secs: f32) -> Self {
+        Invulnerable(Timer::from_seconds(secs, TimerMode::Once))
+    }
+}
+
+const BLINK_INTERVAL_SECS: f32 = 0.08;
+
+pub fn tick_invulnerability(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Invulnerable, Option<&mut Sprite>), Without<Dying>>,
+) {
+    for (entity, mut invulnerable, sprite) in query.iter_mut() {
+        invulnerable.0.tick(time.delta());
+        let finished = invulnerable.0.is_finished();
+
+        // blink while immune so the player can see the window
+        if let Some(mut sprite) = sprite {
+            let visible = finished || (invulnerable.0.elapsed_secs() / BLINK_INTERVAL_SECS) as u32 % 2 == 1;
+            sprite.color.set_alpha(if visible { 1.0 } else { 0.35 });
+        }
+
+        if finished {
+            commands.entity(entity).remove::<Invulnerable>();
+        }
+    }
+}
@@ -1,5 +1,5 @@
 pub mod position;
-pub mod move_speed;
 pub mod char_state;
 pub mod health;
 pub mod stats;
+pub mod invulnerability;
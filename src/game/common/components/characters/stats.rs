@@ -1,12 +1,22 @@
-use bevy::ecs::component::Component;
+use bevy::prelude::*;
 use serde::Deserialize;
 
-#[derive(Deserialize, Component)]
+use crate::game::common::components::characters::health::Health;
+use crate::game::config as cfg;
+use crate::game::game_state::GameState;
+
+// Base stats, as authored in class / enemy data. Gameplay reads
+// `EffectiveStats`, which is `Stats` with every `StatModifiers` entry applied.
+#[derive(Deserialize, Component, Clone, Debug)]
+#[require(StatModifiers, EffectiveStats)]
 pub struct Stats {
     pub damage: f32,
     pub health: f32,
     pub attack_speed: f32,
     pub move_speed: f32,
+    // radius in which pickups are pulled towards the entity
+    #[serde(default = "default_pickup_radius")]
+    pub pickup_radius: f32,
 }
 
 impl Default for Stats {
@@ -16,6 +26,158 @@ impl Default for Stats {
             health: 100.0,
             attack_speed: 1.0,
             move_speed: 75.0,
+            pickup_radius: default_pickup_radius(),
+        }
+    }
+}
+
+fn default_pickup_radius() -> f32 {
+    cfg::PLAYER_MAGNET_RADIUS
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+pub enum StatKind {
+    Damage,
+    Health,
+    AttackSpeed,
+    MoveSpeed,
+    PickupRadius,
+}
+
+// `flat` is added to the base value, `percent` is summed with the other
+// modifiers on the same stat and applied afterwards (0.1 = +10%).
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct StatModifier {
+    pub stat: StatKind,
+    #[serde(default)]
+    pub flat: f32,
+    #[serde(default)]
+    pub percent: f32,
+}
+
+// Where a modifier came from, so it can be removed again (unequipping an
+// item, a buff wearing off, ...).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ModifierSource {
+    Item(String),
+    Buff(String),
+    LevelUp,
+    Upgrade(String),
+    // difficulty scaling from the wave director
+    Director,
+    // elite affix, by id
+    Affix(String),
+}
+
+#[derive(Clone, Debug)]
+pub struct AppliedModifier {
+    pub source: ModifierSource,
+    pub modifier: StatModifier,
+    // seconds left for timed buffs; `None` lasts until removed
+    pub remaining: Option<f32>,
+}
+
+#[derive(Component, Default, Clone, Debug)]
+pub struct StatModifiers(pub Vec<AppliedModifier>);
+
+impl StatModifiers {
+    pub fn add(&mut self, source: ModifierSource, modifier: StatModifier) {
+        self.0.push(AppliedModifier { source, modifier, remaining: None });
+    }
+
+    pub fn add_timed(&mut self, source: ModifierSource, modifier: StatModifier, secs: f32) {
+        self.0.push(AppliedModifier { source, modifier, remaining: Some(secs) });
+    }
+
+    pub fn count_from(&self, source: &ModifierSource) -> usize {
+        self.0.iter().filter(|m| m.source == *source).count()
+    }
+}
+
+// Resolved stat values. Kept up to date by `resolve_stats`; don't write to it
+// directly, change `Stats` or `StatModifiers` instead.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct EffectiveStats {
+    pub damage: f32,
+    pub health: f32,
+    pub attack_speed: f32,
+    pub move_speed: f32,
+    pub pickup_radius: f32,
+}
+
+impl Default for EffectiveStats {
+    fn default() -> Self {
+        EffectiveStats::resolve(&Stats::default(), &StatModifiers::default())
+    }
+}
+
+impl EffectiveStats {
+    pub fn resolve(base: &Stats, modifiers: &StatModifiers) -> Self {
+        let apply = |stat: StatKind, base_value: f32| {
+            let (flat, percent) = modifiers
+                .0
+                .iter()
+                .filter(|m| m.modifier.stat == stat)
+                .fold((0.0, 0.0), |(f, p), m| (f + m.modifier.flat, p + m.modifier.percent));
+            ((base_value + flat) * (1.0 + percent)).max(0.0)
+        };
+
+        EffectiveStats {
+            damage: apply(StatKind::Damage, base.damage),
+            health: apply(StatKind::Health, base.health),
+            attack_speed: apply(StatKind::AttackSpeed, base.attack_speed),
+            move_speed: apply(StatKind::MoveSpeed, base.move_speed),
+            pickup_radius: apply(StatKind::PickupRadius, base.pickup_radius),
+        }
+    }
+}
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PreUpdate, resolve_stats)
+            .add_systems(Update, expire_timed_modifiers.run_if(in_state(GameState::InGame)));
+    }
+}
+
+// Only entities whose base stats or modifiers changed are recomputed, so this
+// stays cheap with large enemy counts.
+fn resolve_stats(
+    mut query: Query<
+        (&Stats, &StatModifiers, &mut EffectiveStats, Option<&mut Health>),
+        Or<(Changed<Stats>, Changed<StatModifiers>)>,
+    >,
+) {
+    for (base, modifiers, mut effective, health) in query.iter_mut() {
+        *effective = EffectiveStats::resolve(base, modifiers);
+
+        // max health follows the stat; gaining max health also heals by the
+        // same amount, losing it just clamps
+        if let Some(mut health) = health {
+            let old_max = health.max;
+            health.max = effective.health;
+            if health.max > old_max {
+                health.current += health.max - old_max;
+            }
+            health.current = health.current.min(health.max);
+        }
+    }
+}
+
+fn expire_timed_modifiers(time: Res<Time>, mut query: Query<&mut StatModifiers>) {
+    let dt = time.delta_secs();
+    for mut modifiers in query.iter_mut() {
+        // avoid triggering change detection for entities without timed buffs
+        if !modifiers.0.iter().any(|m| m.remaining.is_some()) {
+            continue;
+        }
+
+        for m in modifiers.0.iter_mut() {
+            if let Some(remaining) = m.remaining.as_mut() {
+                *remaining -= dt;
+            }
         }
+        modifiers.0.retain(|m| m.remaining.is_none_or(|r| r > 0.0));
     }
 }
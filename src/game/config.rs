@@ -27,21 +27,60 @@ pub const ORTHO_MAX_SCALE: f32 = 0.5;
 pub const ORTHO_GEN_MARGIN: f32 = 0.5;
 pub const MAX_GEN_SCALE: f32 = ORTHO_MAX_SCALE + ORTHO_GEN_MARGIN;
 
-// Gun
-pub const BULLET_SPAWN_INTERVAL: f32 = 0.1;
-pub const BULLET_TIME_SECS: f32 = 1.;
-pub const BULLET_SPEED: f32 = 500.0;
-pub const BULLET_DAMAGE: f32 = 15.0;
-pub const NUM_BULLETS_PER_SHOT: usize = 10;
-pub const BULLET_SPREAD: f32 = 0.5;
+// Player
+pub const DEFAULT_CLASS: &str = "default";
+pub const PLAYER_DEATH_SEQUENCE_SECS: f32 = 1.5;
+pub const PLAYER_INVULNERABILITY_SECS: f32 = 0.5;
+pub const PLAYER_HIT_STOP_SECS: f32 = 0.05;
+pub const PLAYER_MAGNET_RADIUS: f32 = 60.0;
+pub const DEFAULT_WEAPON_SLOTS: usize = 4;
+pub const WEAPON_HOLD_OFFSET: f32 = 20.0;
+pub const AUTO_AIM_RANGE: f32 = 300.0;
+
+// Experience
+pub const XP_GEM_SPRITE: usize = 16;
+pub const XP_PER_KILL: f32 = 1.0;
+pub const XP_PICKUP_RADIUS: f32 = 8.0;
+pub const XP_GEM_SPEED: f32 = 250.0;
+pub const XP_FIRST_LEVEL: f32 = 5.0;
+pub const XP_LEVEL_GROWTH: f32 = 1.25;
+pub const LEVEL_UP_CHOICES: usize = 3;
+
+// Loot
+pub const LOOT_SPRITE: usize = 16;
+// paid out by a weapon chest when the player can't take another weapon
+pub const CHEST_FALLBACK_CURRENCY: u32 = 25;
 
 // Enemy
-pub const ENEMY_HEALTH: f32 = 30.0;
-pub const ENEMY_SPEED: f32 = 50.0;
 pub const MAX_NUM_ENEMIES: usize = 50_000;
-pub const SPAWN_RATE_PER_SECOND: usize = 2;
-pub const ENEMY_SPAWN_INTERVAL: f32 = 0.5;
-pub const ENEMY_DAMAGE: f32 = 10.0;
+pub const ENEMY_CONTACT_RANGE: f32 = 20.0;
+pub const KNOCKBACK_DECAY: f32 = 8.0;
+// enemies closer than this push each other apart
+pub const ENEMY_SEPARATION_RADIUS: f32 = 14.0;
+// separation push as a fraction of the enemy's move speed
+pub const ENEMY_SEPARATION_WEIGHT: f32 = 0.8;
+pub const ELITE_SCALE: f32 = 1.3;
+// size of the copies a splitting elite breaks into
+pub const SPLIT_SCALE: f32 = 0.7;
+pub const SHIELD_AURA_INTERVAL: f32 = 1.0;
+// distance from the player regular spawns appear at, just off-screen
+pub const SPAWN_RING_MIN: f32 = 750.0;
+pub const SPAWN_RING_MAX: f32 = 1000.0;
+pub const ELITE_WAVE_RADIUS: f32 = 800.0;
+// how often enemies are checked against the wave timeline's leash distance
+pub const LEASH_CHECK_INTERVAL: f32 = 0.5;
+// bosses appear this far above the player
+pub const BOSS_SPAWN_DISTANCE: f32 = 400.0;
+// game speed, and real seconds it lasts, of the slow motion when a boss dies
+pub const BOSS_DEATH_SLOW_MOTION: f32 = 0.3;
+pub const BOSS_DEATH_SLOW_MOTION_SECS: f32 = 1.0;
+
+// Navigation
+pub const NAV_CELL_SIZE: f32 = 16.0;
+// flow field covers this many cells in each direction around the player
+pub const FLOW_FIELD_RADIUS: i32 = 64;
+// max cells expanded per frame while recomputing the flow field
+pub const FLOW_FIELD_BUDGET: usize = 4096;
 
 // Kd-tree
 pub const KD_TREE_REFRESH_RATE: f32 = 0.1;
@@ -0,0 +1,246 @@
+use std::f32::consts::PI;
+use std::time::Duration;
+
+use bevy::platform::collections::HashSet;
+use bevy::prelude::*;
+use bevy::time::common_conditions::on_timer;
+use bevy_common_assets::ron::RonAssetPlugin;
+use rand::Rng;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::game::assets::GameAssets;
+use crate::game::common::components::characters::stats::{ModifierSource, StatKind, StatModifier, StatModifiers};
+use crate::game::config as cfg;
+use crate::game::enemies::component::Enemy;
+use crate::game::enemies::enemies_example::EnemyBundle;
+use crate::game::enemies::events::EnemyKilledEvent;
+use crate::game::enemies::registry::{EnemyKind, EnemyRegistry};
+use crate::game::game_state::GameState;
+use crate::game::player::component::Player;
+use crate::game::player::events::PlayerDamagedEvent;
+use crate::game::resources::GlobalTextureAtlas;
+use crate::game::rng::weighted_pick;
+use crate::game::spatial::KDTree2;
+
+// Elite affixes, authored in `entities/enemies/affixes.ron`.
+#[derive(Deserialize, Asset, TypePath)]
+pub struct AffixList {
+    pub affixes: Vec<AffixSpec>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct AffixSpec {
+    pub id: String,
+    // relative chance of being picked among the affixes
+    #[serde(default = "default_weight")]
+    pub weight: f32,
+    #[serde(default)]
+    pub modifiers: Vec<StatModifier>,
+    // sprite colour; an elite with several affixes blends them
+    pub tint: (f32, f32, f32),
+    #[serde(default)]
+    pub effect: Option<AffixEffect>,
+    // added to the loot table's drop chance; every whole point is an extra roll
+    #[serde(default)]
+    pub loot_bonus: f32,
+}
+
+fn default_weight() -> f32 {
+    1.0
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub enum AffixEffect {
+    // heals by this fraction of the contact damage it deals
+    Vampiric { heal_fraction: f32 },
+    // breaks into smaller copies of itself on death
+    Splitting { count: u32 },
+    // damages the player if they're within `radius` when it dies
+    Exploding { radius: f32, damage: f32 },
+    // nearby allies get `shield` extra health for `duration` seconds,
+    // recharged once it wears off
+    ShieldAura { radius: f32, shield: f32, duration: f32 },
+}
+
+#[derive(Resource)]
+pub struct AffixRegistry {
+    affixes: Vec<AffixSpec>,
+}
+
+#[derive(Debug, Error)]
+pub enum AffixError {
+    #[error("affix id `{0}` is defined more than once")]
+    DuplicateId(String),
+    #[error("affix `{id}` has a negative weight {weight}")]
+    NegativeWeight { id: String, weight: f32 },
+}
+
+impl AffixRegistry {
+    pub fn from_list(list: &AffixList) -> Result<Self, AffixError> {
+        let mut ids = HashSet::default();
+        for affix in list.affixes.iter() {
+            if !ids.insert(affix.id.as_str()) {
+                return Err(AffixError::DuplicateId(affix.id.clone()));
+            }
+            if affix.weight < 0.0 {
+                return Err(AffixError::NegativeWeight { id: affix.id.clone(), weight: affix.weight });
+            }
+        }
+        Ok(AffixRegistry { affixes: list.affixes.clone() })
+    }
+
+    // up to `count` distinct affixes, picked by weight
+    pub fn roll(&self, count: usize, rng: &mut impl Rng) -> Vec<&AffixSpec> {
+        let mut pool: Vec<&AffixSpec> = self.affixes.iter().collect();
+        let mut chosen = Vec::with_capacity(count);
+        while chosen.len() < count {
+            let weighted: Vec<(usize, f32)> = pool.iter().enumerate().map(|(i, a)| (i, a.weight)).collect();
+            let Some(&index) = weighted_pick(&weighted, rng) else { break };
+            chosen.push(pool.swap_remove(index));
+        }
+        chosen
+    }
+}
+
+// Marks an enemy promoted to an elite. Effects are handled by the systems in
+// this module; stat changes live in its `StatModifiers`.
+#[derive(Component, Debug)]
+pub struct Elite {
+    pub affixes: Vec<String>,
+    pub effects: Vec<AffixEffect>,
+    pub loot_bonus: f32,
+}
+
+impl Elite {
+    pub fn heal_fraction(&self) -> f32 {
+        self.effects
+            .iter()
+            .map(|e| match e {
+                AffixEffect::Vampiric { heal_fraction } => *heal_fraction,
+                _ => 0.0,
+            })
+            .sum()
+    }
+}
+
+// Turns an enemy that is about to be spawned into an elite: stats, tint and
+// size change here, and the returned `Elite` goes on the entity with it.
+pub fn promote_to_elite(bundle: &mut EnemyBundle, modifiers: &mut StatModifiers, chosen: &[&AffixSpec]) -> Elite {
+    let mut tint = Vec3::ZERO;
+    for affix in chosen {
+        for modifier in affix.modifiers.iter() {
+            modifiers.add(ModifierSource::Affix(affix.id.clone()), *modifier);
+        }
+        tint += Vec3::new(affix.tint.0, affix.tint.1, affix.tint.2);
+    }
+    tint /= chosen.len().max(1) as f32;
+    bundle.sprite.color = Color::srgb(tint.x, tint.y, tint.z);
+    bundle.transform.scale *= cfg::ELITE_SCALE;
+
+    Elite {
+        affixes: chosen.iter().map(|a| a.id.clone()).collect(),
+        effects: chosen.iter().filter_map(|a| a.effect).collect(),
+        loot_bonus: chosen.iter().map(|a| a.loot_bonus).sum(),
+    }
+}
+
+pub struct AffixPlugin;
+
+impl Plugin for AffixPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(RonAssetPlugin::<AffixList>::new(&["affixes.ron"]))
+            .add_systems(OnExit(GameState::Loading), build_affix_registry)
+            .add_systems(
+                Update,
+                apply_shield_auras
+                    .run_if(in_state(GameState::InGame))
+                    .run_if(on_timer(Duration::from_secs_f32(cfg::SHIELD_AURA_INTERVAL))),
+            )
+            .add_observer(trigger_death_affixes);
+    }
+}
+
+// without a registry no enemy is promoted, the run itself still works
+fn build_affix_registry(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    affix_lists: Res<Assets<AffixList>>,
+) {
+    let Some(list) = affix_lists.get(&game_assets.affix_list) else {
+        error!("Affix list entities/enemies/affixes.ron is not loaded");
+        return;
+    };
+
+    match AffixRegistry::from_list(list) {
+        Ok(registry) => {
+            info!("Loaded {} elite affixes", registry.affixes.len());
+            commands.insert_resource(registry);
+        }
+        Err(e) => error!("Invalid affix data in entities/enemies/affixes.ron: {e}"),
+    }
+}
+
+fn apply_shield_auras(
+    tree: Res<KDTree2>,
+    elite_query: Query<(Entity, &Transform, &Elite)>,
+    mut ally_query: Query<&mut StatModifiers, With<Enemy>>,
+) {
+    let source = ModifierSource::Buff(String::from("shield_aura"));
+    for (entity, transform, elite) in elite_query.iter() {
+        for effect in elite.effects.iter() {
+            let AffixEffect::ShieldAura { radius, shield, duration } = *effect else { continue };
+            for (_, ally) in tree.within_distance(transform.translation, radius) {
+                // allies only, the caster doesn't shield itself
+                let Some(ally) = ally.filter(|&a| a != entity) else { continue };
+                let Ok(mut modifiers) = ally_query.get_mut(ally) else { continue };
+                // one shield at a time; it recharges after running out
+                if modifiers.count_from(&source) > 0 {
+                    continue;
+                }
+                let modifier = StatModifier { stat: StatKind::Health, flat: shield, percent: 0.0 };
+                modifiers.add_timed(source.clone(), modifier, duration);
+            }
+        }
+    }
+}
+
+fn trigger_death_affixes(
+    event: On<EnemyKilledEvent>,
+    mut commands: Commands,
+    registry: Option<Res<EnemyRegistry>>,
+    atlas: Res<GlobalTextureAtlas>,
+    elite_query: Query<(&Elite, &EnemyKind, &StatModifiers)>,
+    enemy_query: Query<(), With<Enemy>>,
+    player_query: Query<&Transform, With<Player>>,
+) {
+    let Ok((elite, kind, modifiers)) = elite_query.get(event.entity) else { return };
+
+    for effect in elite.effects.iter() {
+        match *effect {
+            AffixEffect::Splitting { count } => {
+                let Some(registry) = registry.as_ref() else { continue };
+                // the copies keep the difficulty scaling the parent spawned with,
+                // but not its affixes
+                let mut scaling = StatModifiers::default();
+                scaling.0.extend(modifiers.0.iter().filter(|m| m.source == ModifierSource::Director).cloned());
+                let free = cfg::MAX_NUM_ENEMIES.saturating_sub(enemy_query.iter().len());
+                let count = (count as usize).min(free);
+                for i in 0..count {
+                    let angle = i as f32 / count as f32 * PI * 2.0;
+                    let pos = event.position.truncate() + Vec2::from_angle(angle) * 10.0;
+                    let mut bundle = EnemyBundle::from_spec(registry.get(*kind), *kind, &atlas, pos.extend(1.0));
+                    bundle.transform.scale *= cfg::SPLIT_SCALE;
+                    commands.spawn((bundle, scaling.clone()));
+                }
+            }
+            AffixEffect::Exploding { radius, damage } => {
+                let Ok(player_tf) = player_query.single() else { continue };
+                if player_tf.translation.truncate().distance(event.position.truncate()) <= radius {
+                    commands.trigger(PlayerDamagedEvent { damage });
+                }
+            }
+            AffixEffect::Vampiric { .. } | AffixEffect::ShieldAura { .. } => {}
+        }
+    }
+}
@@ -0,0 +1,315 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::game::common::components::characters::health::Health;
+use crate::game::common::components::characters::position::Position;
+use crate::game::common::components::characters::stats::EffectiveStats;
+use crate::game::enemies::component::Enemy;
+use crate::game::game_state::GameState;
+use crate::game::map::navigation::FlowField;
+use crate::game::player::component::Player;
+use crate::game::resources::GlobalTextureAtlas;
+use crate::game::spatial::KDTree2;
+
+// How an enemy moves and attacks, chosen per enemy in its RON spec. New
+// archetypes are made by tuning the parameters, not by adding variants.
+#[derive(Component, Deserialize, Clone, Copy, Debug, Default)]
+pub enum Behaviour {
+    // walks straight at the player
+    #[default]
+    Chaser,
+    // keeps its distance and shoots
+    Ranged(RangedParams),
+    // closes in, winds up, then dashes in a straight line
+    Charger(ChargerParams),
+    // circles the player at a fixed radius
+    Orbiter(OrbiterParams),
+    // stays away from the player and heals nearby enemies, not itself
+    Healer(HealerParams),
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(default)]
+pub struct RangedParams {
+    pub preferred_range: f32,
+    // how far from preferred_range it may drift before moving again
+    pub tolerance: f32,
+    pub fire_range: f32,
+    // seconds between shots at attack_speed 1.0
+    pub fire_interval: f32,
+    pub projectile_speed: f32,
+    pub projectile_lifetime: f32,
+    pub projectile_sprite: usize,
+    // scales the enemy's damage stat
+    pub damage_multiplier: f32,
+}
+
+impl Default for RangedParams {
+    fn default() -> Self {
+        RangedParams {
+            preferred_range: 180.0,
+            tolerance: 30.0,
+            fire_range: 260.0,
+            fire_interval: 2.0,
+            projectile_speed: 160.0,
+            projectile_lifetime: 3.0,
+            projectile_sprite: 16,
+            damage_multiplier: 1.0,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(default)]
+pub struct ChargerParams {
+    // distance at which it stops to wind up
+    pub trigger_range: f32,
+    pub wind_up: f32,
+    pub dash_speed: f32,
+    pub dash_time: f32,
+    // pause after a dash before chasing again
+    pub recover: f32,
+}
+
+impl Default for ChargerParams {
+    fn default() -> Self {
+        ChargerParams {
+            trigger_range: 140.0,
+            wind_up: 0.6,
+            dash_speed: 320.0,
+            dash_time: 0.5,
+            recover: 0.8,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(default)]
+pub struct OrbiterParams {
+    pub radius: f32,
+    pub clockwise: bool,
+}
+
+impl Default for OrbiterParams {
+    fn default() -> Self {
+        OrbiterParams { radius: 120.0, clockwise: false }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(default)]
+pub struct HealerParams {
+    // runs away while the player is closer than this
+    pub flee_range: f32,
+    pub heal_range: f32,
+    pub heal_amount: f32,
+    pub heal_interval: f32,
+}
+
+impl Default for HealerParams {
+    fn default() -> Self {
+        HealerParams {
+            flee_range: 220.0,
+            heal_range: 100.0,
+            heal_amount: 20.0,
+            heal_interval: 2.0,
+        }
+    }
+}
+
+// Runtime state for the behaviours that need it. `timer` counts down to the
+// next shot / heal / charge phase change.
+#[derive(Component, Default)]
+pub struct BehaviourState {
+    pub timer: f32,
+    pub charge: ChargePhase,
+}
+
+#[derive(Default, Clone, Copy, Debug, PartialEq)]
+pub enum ChargePhase {
+    #[default]
+    Approach,
+    WindUp,
+    Dash(Vec2),
+    Recover,
+}
+
+// A shot fired by an enemy; damages the player on contact.
+#[derive(Component)]
+pub struct EnemyProjectile {
+    pub damage: f32,
+    pub velocity: Vec2,
+    pub lifetime: Timer,
+}
+
+pub fn spawn_enemy_projectile(
+    commands: &mut Commands,
+    atlas: &GlobalTextureAtlas,
+    pos: Vec2,
+    projectile: EnemyProjectile,
+    sprite: usize,
+) {
+    commands.spawn((
+        Sprite {
+            color: Color::srgb(1.0, 0.3, 0.3),
+            ..Sprite::from_atlas_image(
+                atlas.image.clone(),
+                TextureAtlas { layout: atlas.layout.clone(), index: sprite },
+            )
+        },
+        Transform::from_translation(pos.extend(9.0)),
+        projectile,
+        DespawnOnExit(GameState::GameOver),
+    ));
+}
+
+pub struct EnemyBehaviourPlugin;
+
+impl Plugin for EnemyBehaviourPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (run_enemy_behaviours, update_enemy_projectiles).run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+fn run_enemy_behaviours(
+    mut commands: Commands,
+    time: Res<Time>,
+    atlas: Res<GlobalTextureAtlas>,
+    tree: Res<KDTree2>,
+    flow_field: Res<FlowField>,
+    player_query: Query<&Position, With<Player>>,
+    mut enemy_query: Query<
+        (Entity, &mut Transform, &mut Sprite, &EffectiveStats, &Behaviour, &mut BehaviourState),
+        (With<Enemy>, Without<Player>),
+    >,
+    mut health_query: Query<&mut Health, With<Enemy>>,
+) {
+    let Ok(player_pos) = player_query.single() else { return };
+    let player_pos = Vec2::new(player_pos.x, player_pos.y);
+    let dt = time.delta_secs();
+
+    for (entity, mut transform, mut sprite, stats, behaviour, mut state) in enemy_query.iter_mut() {
+        let pos = transform.translation.truncate();
+        let to_player = player_pos - pos;
+        let dist = to_player.length();
+        if dist <= f32::EPSILON {
+            continue;
+        }
+        let dir = to_player / dist;
+        // way around walls when approaching; retreating and strafing stay direct
+        let path = flow_field.direction_at(pos).unwrap_or(dir);
+        let speed = stats.move_speed;
+        state.timer -= dt;
+
+        let velocity = match *behaviour {
+            Behaviour::Chaser => path * speed,
+
+            Behaviour::Ranged(p) => {
+                if state.timer <= 0.0 && dist <= p.fire_range {
+                    state.timer = p.fire_interval / stats.attack_speed.max(0.01);
+                    spawn_enemy_projectile(
+                        &mut commands,
+                        &atlas,
+                        pos,
+                        EnemyProjectile {
+                            damage: stats.damage * p.damage_multiplier,
+                            velocity: dir * p.projectile_speed,
+                            lifetime: Timer::from_seconds(p.projectile_lifetime, TimerMode::Once),
+                        },
+                        p.projectile_sprite,
+                    );
+                }
+                if dist > p.preferred_range + p.tolerance {
+                    path * speed
+                } else if dist < p.preferred_range - p.tolerance {
+                    -dir * speed
+                } else {
+                    Vec2::ZERO
+                }
+            }
+
+            Behaviour::Charger(p) => match state.charge {
+                ChargePhase::Approach => {
+                    if dist <= p.trigger_range {
+                        state.charge = ChargePhase::WindUp;
+                        state.timer = p.wind_up;
+                    }
+                    path * speed
+                }
+                ChargePhase::WindUp => {
+                    // the dash direction is locked in when the wind-up ends
+                    if state.timer <= 0.0 {
+                        state.charge = ChargePhase::Dash(dir);
+                        state.timer = p.dash_time;
+                    }
+                    Vec2::ZERO
+                }
+                ChargePhase::Dash(dash_dir) => {
+                    if state.timer <= 0.0 {
+                        state.charge = ChargePhase::Recover;
+                        state.timer = p.recover;
+                    }
+                    dash_dir * p.dash_speed
+                }
+                ChargePhase::Recover => {
+                    if state.timer <= 0.0 {
+                        state.charge = ChargePhase::Approach;
+                    }
+                    Vec2::ZERO
+                }
+            },
+
+            Behaviour::Orbiter(p) => {
+                let tangent = if p.clockwise { Vec2::new(dir.y, -dir.x) } else { dir.perp() };
+                // pull towards the ring, full strength one radius away from it
+                let radial = ((dist - p.radius) / p.radius.max(1.0)).clamp(-1.0, 1.0);
+                (tangent + dir * radial).normalize_or_zero() * speed
+            }
+
+            Behaviour::Healer(p) => {
+                if state.timer <= 0.0 {
+                    state.timer = p.heal_interval;
+                    for (_, ally) in tree.within_distance(transform.translation, p.heal_range) {
+                        // allies only; a self-healing support is near unkillable
+                        let Some(ally) = ally.filter(|&a| a != entity) else { continue };
+                        if let Ok(mut health) = health_query.get_mut(ally) {
+                            if !health.is_dead() {
+                                health.heal(p.heal_amount);
+                            }
+                        }
+                    }
+                }
+                // hover just outside flee_range so it stays near the fight
+                if dist < p.flee_range {
+                    -dir * speed
+                } else if dist > p.flee_range * 1.5 {
+                    path * speed * 0.5
+                } else {
+                    Vec2::ZERO
+                }
+            }
+        };
+
+        transform.translation += velocity.extend(0.0) * dt;
+
+        // flip sprite to face player horizontally
+        sprite.flip_x = player_pos.x <= transform.translation.x;
+    }
+}
+
+fn update_enemy_projectiles(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut projectile_query: Query<(Entity, &mut Transform, &mut EnemyProjectile)>,
+) {
+    for (entity, mut transform, mut projectile) in projectile_query.iter_mut() {
+        if projectile.lifetime.tick(time.delta()).is_finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        transform.translation += projectile.velocity.extend(0.0) * time.delta_secs();
+    }
+}
@@ -0,0 +1,353 @@
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+use bevy_common_assets::ron::RonAssetPlugin;
+use serde::Deserialize;
+
+use crate::game::assets::GameAssets;
+use crate::game::common::components::characters::health::Health;
+use crate::game::common::components::characters::stats::EffectiveStats;
+use crate::game::config as cfg;
+use crate::game::enemies::component::Enemy;
+use crate::game::enemies::director::{current_scaling, ResolvedTimeline};
+use crate::game::enemies::behaviour::{spawn_enemy_projectile, Behaviour, BehaviourState, EnemyProjectile};
+use crate::game::enemies::enemies_example::EnemyBundle;
+use crate::game::enemies::events::EnemyKilledEvent;
+use crate::game::enemies::registry::{build_registry, EnemyRegistry};
+use crate::game::game_state::GameState;
+use crate::game::player::component::Player;
+use crate::game::resources::GlobalTextureAtlas;
+use crate::game::run::RunStats;
+use crate::game::time_scale::TimeScale;
+
+#[derive(Deserialize, Asset, TypePath)]
+pub struct BossList {
+    pub bosses: Vec<BossSpec>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct BossSpec {
+    pub id: String,
+    // shown on the boss health bar
+    pub name: String,
+    // enemy from devil.ron the boss is built on (sprite, animation, base stats)
+    pub enemy: String,
+    pub health: f32,
+    #[serde(default = "default_boss_scale")]
+    pub scale: f32,
+    // in order; each starts once health drops below its `below` fraction
+    pub phases: Vec<BossPhase>,
+    #[serde(default)]
+    pub on_defeat: BossOutcome,
+}
+
+fn default_boss_scale() -> f32 {
+    2.5
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct BossPhase {
+    // fraction of max health below which this phase starts
+    #[serde(default = "default_phase_threshold")]
+    pub below: f32,
+    // movement for the phase; a Charger makes the boss charge
+    #[serde(default)]
+    pub behaviour: Behaviour,
+    pub attack_interval: f32,
+    // used in turn, one every attack_interval seconds
+    #[serde(default)]
+    pub attacks: Vec<BossAttack>,
+}
+
+fn default_phase_threshold() -> f32 {
+    1.0
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub enum BossAttack {
+    // projectiles fired outwards in a circle
+    BulletRing {
+        count: u32,
+        speed: f32,
+        #[serde(default = "default_damage_multiplier")]
+        damage_multiplier: f32,
+        // rotation added to each successive ring, in degrees
+        #[serde(default)]
+        twist: f32,
+        // seconds before a projectile disappears
+        #[serde(default = "default_ring_lifetime")]
+        lifetime: f32,
+        #[serde(default = "default_ring_sprite")]
+        sprite: usize,
+    },
+    // calls in regular enemies around the boss
+    Summon {
+        enemy: String,
+        count: u32,
+        #[serde(default = "default_summon_radius")]
+        radius: f32,
+    },
+}
+
+fn default_damage_multiplier() -> f32 {
+    1.0
+}
+
+fn default_ring_lifetime() -> f32 {
+    4.0
+}
+
+fn default_ring_sprite() -> usize {
+    16
+}
+
+fn default_summon_radius() -> f32 {
+    80.0
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BossOutcome {
+    // the run goes on, e.g. into the next stage
+    #[default]
+    Continue,
+    // beating the boss wins the run
+    EndRun,
+}
+
+#[derive(Resource)]
+pub struct BossRegistry {
+    specs: Vec<BossSpec>,
+}
+
+impl BossRegistry {
+    pub fn get(&self, id: &str) -> Option<&BossSpec> {
+        self.specs.iter().find(|s| s.id == id)
+    }
+}
+
+#[derive(Component)]
+pub struct Boss {
+    pub id: String,
+    pub name: String,
+    pub phase: usize,
+    attack_timer: Timer,
+    next_attack: usize,
+    rings_fired: u32,
+}
+
+// asks for a boss to be spawned near the player; the wave director sends it
+// from the timeline, anything else may too
+#[derive(Event)]
+pub struct SpawnBossEvent {
+    pub id: String,
+}
+
+// fired when a boss dies, before it is despawned
+#[derive(Event)]
+pub struct BossDefeatedEvent {
+    pub id: String,
+    pub outcome: BossOutcome,
+}
+
+pub struct BossPlugin;
+
+impl Plugin for BossPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(RonAssetPlugin::<BossList>::new(&["bosses.ron"]))
+            .add_systems(OnExit(GameState::Loading), build_boss_registry.after(build_registry))
+            .add_systems(Update, (update_boss_phases, run_boss_attacks).chain().run_if(in_state(GameState::InGame)))
+            .add_observer(spawn_boss)
+            .add_observer(detect_boss_defeat)
+            .add_observer(apply_boss_outcome);
+    }
+}
+
+// a boss pointing at an unknown enemy is an authoring error: log it and leave
+// the registry out, same as the enemy registry does
+pub(crate) fn build_boss_registry(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    boss_lists: Res<Assets<BossList>>,
+    registry: Option<Res<EnemyRegistry>>,
+) {
+    let Some(registry) = registry else { return };
+    let Some(list) = boss_lists.get(&game_assets.boss_list) else {
+        error!("Boss list entities/bosses/bosses.ron is not loaded");
+        return;
+    };
+
+    for spec in list.bosses.iter() {
+        let referenced_by = format!("boss `{}`", spec.id);
+        let summons = spec.phases.iter().flat_map(|p| p.attacks.iter()).filter_map(|a| match a {
+            BossAttack::Summon { enemy, .. } => Some(enemy),
+            _ => None,
+        });
+        for id in std::iter::once(&spec.enemy).chain(summons) {
+            if let Err(e) = registry.resolve(id, &referenced_by) {
+                error!("Invalid boss data in entities/bosses/bosses.ron: {e}");
+                return;
+            }
+        }
+        if spec.phases.is_empty() {
+            error!("Invalid boss data in entities/bosses/bosses.ron: boss `{}` has no phases", spec.id);
+            return;
+        }
+    }
+
+    info!("Loaded {} bosses", list.bosses.len());
+    commands.insert_resource(BossRegistry { specs: list.bosses.clone() });
+}
+
+fn spawn_boss(
+    evt: On<SpawnBossEvent>,
+    mut commands: Commands,
+    bosses: Option<Res<BossRegistry>>,
+    registry: Option<Res<EnemyRegistry>>,
+    atlas: Res<GlobalTextureAtlas>,
+    player_query: Query<&Transform, With<Player>>,
+) {
+    let (Some(bosses), Some(registry)) = (bosses, registry) else { return };
+    let Some(spec) = bosses.get(&evt.event().id) else {
+        error!("Unknown boss `{}`", evt.event().id);
+        return;
+    };
+    let Ok(player_tf) = player_query.single() else { return };
+
+    let kind = registry.kind(&spec.enemy).unwrap();
+    let pos = player_tf.translation.truncate() + Vec2::Y * cfg::BOSS_SPAWN_DISTANCE;
+    let mut bundle = EnemyBundle::from_spec(registry.get(kind), kind, &atlas, pos.extend(1.0));
+    bundle.stats.health = spec.health;
+    bundle.health = Health::new(spec.health);
+    bundle.transform.scale *= spec.scale;
+    bundle.behaviour = spec.phases[0].behaviour;
+
+    info!("Boss {} appears", spec.name);
+    commands.spawn((
+        bundle,
+        Boss {
+            id: spec.id.clone(),
+            name: spec.name.clone(),
+            phase: 0,
+            attack_timer: Timer::from_seconds(spec.phases[0].attack_interval, TimerMode::Repeating),
+            next_attack: 0,
+            rings_fired: 0,
+        },
+    ));
+}
+
+// phases only move forward, so healing a boss doesn't send it back
+fn update_boss_phases(
+    bosses: Option<Res<BossRegistry>>,
+    mut boss_query: Query<(&mut Boss, &Health, &mut Behaviour, &mut BehaviourState)>,
+) {
+    let Some(bosses) = bosses else { return };
+    for (mut boss, health, mut behaviour, mut state) in boss_query.iter_mut() {
+        let Some(spec) = bosses.get(&boss.id) else { continue };
+        let fraction = health.current / health.max.max(1.0);
+
+        let mut phase = boss.phase;
+        while phase + 1 < spec.phases.len() && fraction < spec.phases[phase + 1].below {
+            phase += 1;
+        }
+        if phase == boss.phase {
+            continue;
+        }
+
+        let next = &spec.phases[phase];
+        info!("Boss {} enters phase {}", spec.name, phase + 1);
+        boss.phase = phase;
+        boss.attack_timer = Timer::from_seconds(next.attack_interval, TimerMode::Repeating);
+        boss.next_attack = 0;
+        *behaviour = next.behaviour;
+        *state = BehaviourState::default();
+    }
+}
+
+fn run_boss_attacks(
+    mut commands: Commands,
+    time: Res<Time>,
+    run_stats: Res<RunStats>,
+    bosses: Option<Res<BossRegistry>>,
+    registry: Option<Res<EnemyRegistry>>,
+    timeline: Option<Res<ResolvedTimeline>>,
+    atlas: Res<GlobalTextureAtlas>,
+    mut boss_query: Query<(&mut Boss, &Transform, &EffectiveStats)>,
+    enemy_query: Query<(), With<Enemy>>,
+) {
+    let (Some(bosses), Some(registry)) = (bosses, registry) else { return };
+    for (mut boss, transform, stats) in boss_query.iter_mut() {
+        let Some(spec) = bosses.get(&boss.id) else { continue };
+        let phase = &spec.phases[boss.phase];
+        if !boss.attack_timer.tick(time.delta()).just_finished() || phase.attacks.is_empty() {
+            continue;
+        }
+
+        let attack = &phase.attacks[boss.next_attack % phase.attacks.len()];
+        boss.next_attack += 1;
+        let pos = transform.translation.truncate();
+
+        match attack {
+            BossAttack::BulletRing { count, speed, damage_multiplier, twist, lifetime, sprite } => {
+                let offset = (boss.rings_fired as f32 * twist).to_radians();
+                boss.rings_fired += 1;
+                for i in 0..*count {
+                    let angle = offset + i as f32 / *count as f32 * PI * 2.0;
+                    spawn_enemy_projectile(
+                        &mut commands,
+                        &atlas,
+                        pos,
+                        EnemyProjectile {
+                            damage: stats.damage * damage_multiplier,
+                            velocity: Vec2::from_angle(angle) * *speed,
+                            lifetime: Timer::from_seconds(*lifetime, TimerMode::Once),
+                        },
+                        *sprite,
+                    );
+                }
+            }
+            BossAttack::Summon { enemy, count, radius } => {
+                let kind = registry.kind(enemy).unwrap();
+                // summons scale like the director's spawns and count against the same cap
+                let scaling = timeline.as_deref().map(|t| current_scaling(t, &run_stats)).unwrap_or_default();
+                let free = cfg::MAX_NUM_ENEMIES.saturating_sub(enemy_query.iter().len());
+                let count = (*count as usize).min(free);
+                for i in 0..count {
+                    let angle = i as f32 / count as f32 * PI * 2.0;
+                    let at = pos + Vec2::from_angle(angle) * *radius;
+                    let bundle = EnemyBundle::from_spec(registry.get(kind), kind, &atlas, at.extend(1.0));
+                    commands.spawn((bundle, scaling.clone()));
+                }
+            }
+        }
+    }
+}
+
+fn detect_boss_defeat(
+    evt: On<EnemyKilledEvent>,
+    mut commands: Commands,
+    bosses: Option<Res<BossRegistry>>,
+    boss_query: Query<&Boss>,
+) {
+    let Ok(boss) = boss_query.get(evt.event().entity) else { return };
+    let outcome = bosses
+        .as_ref()
+        .and_then(|b| b.get(&boss.id))
+        .map(|spec| spec.on_defeat)
+        .unwrap_or_default();
+    info!("Boss {} defeated", boss.name);
+    commands.trigger(BossDefeatedEvent { id: boss.id.clone(), outcome });
+}
+
+fn apply_boss_outcome(
+    evt: On<BossDefeatedEvent>,
+    mut run_stats: ResMut<RunStats>,
+    mut time_scale: ResMut<TimeScale>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    run_stats.bosses_defeated += 1;
+    time_scale.slow_motion(cfg::BOSS_DEATH_SLOW_MOTION, cfg::BOSS_DEATH_SLOW_MOTION_SECS);
+    if evt.event().outcome == BossOutcome::EndRun {
+        run_stats.victory = true;
+        next_state.set(GameState::GameOver);
+    }
+}
@@ -1,13 +1,37 @@
-use bevy::prelude::Component;
+use bevy::prelude::{Component, Vec2};
 
 use crate::game::common::components::characters::{
     health::Health,
+    stats::Stats,
 };
 
 #[derive(Component, Default)]
-#[require(Health)]
+#[require(Health, Stats, AttackCooldown)]
 pub struct Enemy;
 
+// Elapsed game time (seconds) at which this enemy may deal contact damage
+// again. Stored as a timestamp so idle enemies cost nothing per frame.
+#[derive(Component, Default)]
+pub struct AttackCooldown {
+    pub ready_at: f64,
+}
+
+impl AttackCooldown {
+    pub fn is_ready(&self, now: f64) -> bool {
+        now >= self.ready_at
+    }
+
+    // attack_speed is in attacks per second
+    pub fn trigger(&mut self, now: f64, attack_speed: f32) {
+        self.ready_at = now + 1.0 / attack_speed.max(0.01) as f64;
+    }
+}
+
+// Push velocity added on top of the enemy's own movement; decays each frame
+// and is removed once it is negligible.
+#[derive(Component)]
+pub struct Knockback(pub Vec2);
+
 impl Enemy {
     pub fn new() -> Self {
         Enemy
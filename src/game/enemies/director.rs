@@ -0,0 +1,576 @@
+use std::f32::consts::PI;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::time::common_conditions::on_timer;
+use bevy_common_assets::ron::RonAssetPlugin;
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::game::assets::GameAssets;
+use crate::game::common::components::characters::stats::{ModifierSource, StatKind, StatModifier, StatModifiers};
+use crate::game::config as cfg;
+use crate::game::enemies::affixes::{promote_to_elite, AffixRegistry, AffixSpec};
+use crate::game::enemies::behaviour::BehaviourState;
+use crate::game::enemies::boss::{build_boss_registry, Boss, BossRegistry, SpawnBossEvent};
+use crate::game::enemies::component::Enemy;
+use crate::game::enemies::enemies_example::EnemyBundle;
+use crate::game::enemies::registry::{build_registry, EnemyKind, EnemyRegistry};
+use crate::game::game_state::GameState;
+use crate::game::player::component::Player;
+use crate::game::resources::GlobalTextureAtlas;
+use crate::game::rng::{weighted_pick, GameRng, RngStream};
+use crate::game::run::RunStats;
+
+// The pacing of a run, authored in `waves/timeline.ron`. Times are in minutes
+// of survived run time.
+#[derive(Deserialize, Asset, TypePath)]
+pub struct WaveTimeline {
+    // enemies spawned per minute
+    pub spawns_per_minute: Keyframes,
+    #[serde(default)]
+    pub health_multiplier: Keyframes,
+    #[serde(default)]
+    pub speed_multiplier: Keyframes,
+    // chance for a regular spawn to be promoted to an elite
+    #[serde(default = "no_elites")]
+    pub elite_chance: Keyframes,
+    // an elite gets between one and this many affixes
+    #[serde(default)]
+    pub max_affixes: Keyframes,
+    pub pool: Vec<PoolEntry>,
+    #[serde(default)]
+    pub events: Vec<ScriptedEvent>,
+    #[serde(default)]
+    pub leash: Leash,
+}
+
+// What happens to enemies that end up too far from the player to matter.
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(default)]
+pub struct Leash {
+    pub distance: f32,
+    pub policy: LeashPolicy,
+}
+
+impl Default for Leash {
+    fn default() -> Self {
+        Leash { distance: 1400.0, policy: LeashPolicy::Teleport }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LeashPolicy {
+    // moved back into the spawn ring, ahead of where the player is heading
+    Teleport,
+    // removed, and the spawn budget gets it back
+    Despawn,
+}
+
+// (minute, value) points in time order, linearly interpolated and held flat
+// past either end. No points means a constant 1.0; two points at the same
+// minute make a step.
+#[derive(Deserialize, Default, Clone, Debug)]
+#[serde(transparent)]
+pub struct Keyframes(pub Vec<(f32, f32)>);
+
+impl Keyframes {
+    pub fn is_sorted(&self) -> bool {
+        self.0.windows(2).all(|pair| pair[0].0 <= pair[1].0)
+    }
+
+    pub fn sample(&self, minute: f32) -> f32 {
+        let points = &self.0;
+        let Some(first) = points.first() else { return 1.0 };
+        if minute <= first.0 {
+            return first.1;
+        }
+        for pair in points.windows(2) {
+            let ((t0, v0), (t1, v1)) = (pair[0], pair[1]);
+            if minute <= t1 {
+                let t = if t1 > t0 { (minute - t0) / (t1 - t0) } else { 1.0 };
+                return v0 + (v1 - v0) * t;
+            }
+        }
+        points.last().unwrap().1
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct PoolEntry {
+    pub enemy: String,
+    // defaults to the enemy's metadata.spawn_rate
+    #[serde(default)]
+    pub weight: Option<f32>,
+    #[serde(default)]
+    pub from_minute: f32,
+    #[serde(default)]
+    pub until_minute: Option<f32>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct ScriptedEvent {
+    pub at_minute: f32,
+    pub event: WaveEvent,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub enum WaveEvent {
+    // a ring of enemies closing in from every side at once
+    Swarm {
+        enemy: String,
+        count: u32,
+        #[serde(default = "default_swarm_radius")]
+        radius: f32,
+    },
+    // a few tougher, bigger copies of an enemy; the multipliers apply on top
+    // of the difficulty curve's, so 3.0 at a 1.5x curve gives 4.5x health
+    EliteWave {
+        enemy: String,
+        count: u32,
+        #[serde(default = "default_elite_multiplier")]
+        health_multiplier: f32,
+        #[serde(default = "default_elite_multiplier")]
+        damage_multiplier: f32,
+        #[serde(default = "default_elite_scale")]
+        scale: f32,
+    },
+    // a boss from entities/bosses/bosses.ron
+    Boss { boss: String },
+}
+
+fn no_elites() -> Keyframes {
+    Keyframes(vec![(0.0, 0.0)])
+}
+
+fn default_swarm_radius() -> f32 {
+    450.0
+}
+
+fn default_elite_multiplier() -> f32 {
+    3.0
+}
+
+fn default_elite_scale() -> f32 {
+    1.5
+}
+
+// The timeline with enemy ids resolved against the registry.
+#[derive(Resource)]
+pub(crate) struct ResolvedTimeline {
+    spawns_per_minute: Keyframes,
+    health_multiplier: Keyframes,
+    speed_multiplier: Keyframes,
+    elite_chance: Keyframes,
+    max_affixes: Keyframes,
+    leash: Leash,
+    pool: Vec<ResolvedPoolEntry>,
+    // sorted by time; boss events carry no enemy kind
+    events: Vec<(f32, WaveEvent, Option<EnemyKind>)>,
+}
+
+struct ResolvedPoolEntry {
+    kind: EnemyKind,
+    weight: f32,
+    from_minute: f32,
+    until_minute: f32,
+}
+
+impl ResolvedPoolEntry {
+    fn unlocked(&self, minute: f32) -> bool {
+        minute >= self.from_minute && minute < self.until_minute
+    }
+}
+
+// Progress through the timeline for the current run.
+#[derive(Resource, Default)]
+struct WaveDirector {
+    // fractional spawns carried over between frames
+    budget: f32,
+    next_event: usize,
+    // player position at the previous leash check, to tell where they're heading
+    last_player_pos: Option<Vec2>,
+}
+
+pub struct WaveDirectorPlugin;
+
+impl Plugin for WaveDirectorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(RonAssetPlugin::<WaveTimeline>::new(&["timeline.ron"]))
+            .init_resource::<WaveDirector>()
+            .add_systems(OnExit(GameState::Loading), resolve_timeline.after(build_registry).after(build_boss_registry))
+            .add_systems(
+                OnTransition { exited: GameState::GameOver, entered: GameState::InGame },
+                reset_director,
+            )
+            .add_systems(Update, (run_scripted_events, spawn_from_budget).run_if(in_state(GameState::InGame)))
+            .add_systems(
+                Update,
+                leash_stragglers
+                    .run_if(in_state(GameState::InGame))
+                    .run_if(on_timer(Duration::from_secs_f32(cfg::LEASH_CHECK_INTERVAL))),
+            );
+    }
+}
+
+// like the registry, a broken timeline is logged and leaves the resource
+// missing, so nothing spawns
+fn resolve_timeline(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    timelines: Res<Assets<WaveTimeline>>,
+    registry: Option<Res<EnemyRegistry>>,
+    bosses: Option<Res<BossRegistry>>,
+) {
+    let Some(registry) = registry else { return };
+    let Some(timeline) = timelines.get(&game_assets.wave_timeline) else {
+        error!("Wave timeline waves/timeline.ron is not loaded");
+        return;
+    };
+
+    let curves = [
+        ("spawns_per_minute", &timeline.spawns_per_minute),
+        ("health_multiplier", &timeline.health_multiplier),
+        ("speed_multiplier", &timeline.speed_multiplier),
+        ("elite_chance", &timeline.elite_chance),
+        ("max_affixes", &timeline.max_affixes),
+    ];
+    if let Some((name, _)) = curves.iter().find(|(_, curve)| !curve.is_sorted()) {
+        error!("Invalid wave timeline: `{name}` keyframes are not in time order");
+        return;
+    }
+
+    let mut pool = Vec::with_capacity(timeline.pool.len());
+    for entry in timeline.pool.iter() {
+        let kind = match registry.resolve(&entry.enemy, "the wave timeline pool") {
+            Ok(kind) => kind,
+            Err(e) => {
+                error!("Invalid wave timeline: {e}");
+                return;
+            }
+        };
+        pool.push(ResolvedPoolEntry {
+            kind,
+            weight: entry.weight.unwrap_or(registry.get(kind).metadata.spawn_rate).max(0.0),
+            from_minute: entry.from_minute,
+            until_minute: entry.until_minute.unwrap_or(f32::INFINITY),
+        });
+    }
+
+    let mut events = Vec::with_capacity(timeline.events.len());
+    for scripted in timeline.events.iter() {
+        let id = match &scripted.event {
+            WaveEvent::Swarm { enemy, .. } | WaveEvent::EliteWave { enemy, .. } => enemy,
+            WaveEvent::Boss { boss } => {
+                if bosses.as_ref().and_then(|b| b.get(boss)).is_none() {
+                    error!("Invalid wave timeline: unknown boss `{boss}` referenced by a wave timeline event");
+                    return;
+                }
+                events.push((scripted.at_minute, scripted.event.clone(), None));
+                continue;
+            }
+        };
+        let kind = match registry.resolve(id, "a wave timeline event") {
+            Ok(kind) => kind,
+            Err(e) => {
+                error!("Invalid wave timeline: {e}");
+                return;
+            }
+        };
+        events.push((scripted.at_minute, scripted.event.clone(), Some(kind)));
+    }
+    events.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    // a leash inside the spawn ring would catch every enemy as it spawns
+    let furthest_spawn = timeline
+        .events
+        .iter()
+        .filter_map(|e| match e.event {
+            WaveEvent::Swarm { radius, .. } => Some(radius),
+            _ => None,
+        })
+        .fold(cfg::SPAWN_RING_MAX.max(cfg::ELITE_WAVE_RADIUS), f32::max);
+    if timeline.leash.distance <= furthest_spawn {
+        error!(
+            "Invalid wave timeline: leash distance {} must be above the furthest spawn distance {}",
+            timeline.leash.distance, furthest_spawn
+        );
+        return;
+    }
+
+    info!("Loaded wave timeline with {} pool entries and {} events", pool.len(), events.len());
+    commands.insert_resource(ResolvedTimeline {
+        spawns_per_minute: timeline.spawns_per_minute.clone(),
+        health_multiplier: timeline.health_multiplier.clone(),
+        speed_multiplier: timeline.speed_multiplier.clone(),
+        elite_chance: timeline.elite_chance.clone(),
+        max_affixes: timeline.max_affixes.clone(),
+        leash: timeline.leash,
+        pool,
+        events,
+    });
+}
+
+fn reset_director(mut director: ResMut<WaveDirector>) {
+    *director = WaveDirector::default();
+}
+
+fn spawn_from_budget(
+    mut commands: Commands,
+    time: Res<Time>,
+    run_stats: Res<RunStats>,
+    timeline: Option<Res<ResolvedTimeline>>,
+    registry: Option<Res<EnemyRegistry>>,
+    affixes: Option<Res<AffixRegistry>>,
+    mut director: ResMut<WaveDirector>,
+    atlas: Res<GlobalTextureAtlas>,
+    player_query: Query<&Transform, With<Player>>,
+    enemy_query: Query<(), With<Enemy>>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    let (Some(timeline), Some(registry)) = (timeline, registry) else { return };
+    let Ok(player_tf) = player_query.single() else { return };
+    let minute = run_stats.time_survived.elapsed_secs() / 60.0;
+
+    director.budget += timeline.spawns_per_minute.sample(minute) / 60.0 * time.delta_secs();
+    let free = cfg::MAX_NUM_ENEMIES.saturating_sub(enemy_query.iter().len());
+    let count = (director.budget.floor() as usize).min(free);
+    if count == 0 {
+        return;
+    }
+    director.budget -= count as f32;
+
+    let unlocked: Vec<(EnemyKind, f32)> =
+        timeline.pool.iter().filter(|e| e.unlocked(minute)).map(|e| (e.kind, e.weight)).collect();
+
+    let modifiers = scaling_modifiers(&timeline, minute, 1.0, 1.0);
+    let player_pos = player_tf.translation.truncate();
+    let rng = game_rng.stream(RngStream::EnemySpawns);
+    for _ in 0..count {
+        let Some(&kind) = weighted_pick(&unlocked, rng) else { return };
+        let pos = get_random_position_around(player_pos, rng);
+        let chosen = match affixes.as_deref() {
+            Some(affixes) if rng.random::<f32>() < timeline.elite_chance.sample(minute) => {
+                affixes.roll(affix_count(&timeline, minute, rng), rng)
+            }
+            _ => Vec::new(),
+        };
+        let bundle = EnemyBundle::from_spec(registry.get(kind), kind, &atlas, pos.extend(1.0));
+        spawn_enemy(&mut commands, bundle, modifiers.clone(), &chosen);
+    }
+}
+
+fn run_scripted_events(
+    mut commands: Commands,
+    run_stats: Res<RunStats>,
+    timeline: Option<Res<ResolvedTimeline>>,
+    registry: Option<Res<EnemyRegistry>>,
+    affixes: Option<Res<AffixRegistry>>,
+    mut director: ResMut<WaveDirector>,
+    atlas: Res<GlobalTextureAtlas>,
+    player_query: Query<&Transform, With<Player>>,
+    enemy_query: Query<(), With<Enemy>>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    let (Some(timeline), Some(registry)) = (timeline, registry) else { return };
+    let Ok(player_tf) = player_query.single() else { return };
+    let minute = run_stats.time_survived.elapsed_secs() / 60.0;
+    let player_pos = player_tf.translation.truncate();
+    let mut free = cfg::MAX_NUM_ENEMIES.saturating_sub(enemy_query.iter().len());
+    let rng = game_rng.stream(RngStream::EnemySpawns);
+
+    while let Some((at, event, kind)) = timeline.events.get(director.next_event) {
+        if *at > minute {
+            break;
+        }
+        director.next_event += 1;
+
+        let Some(kind) = kind else {
+            if let WaveEvent::Boss { boss } = event {
+                info!("Wave event at {:.1} min: boss {}", minute, boss);
+                commands.trigger(SpawnBossEvent { id: boss.clone() });
+            }
+            continue;
+        };
+        let spec = registry.get(*kind);
+        match event {
+            WaveEvent::Swarm { count, radius, .. } => {
+                info!("Wave event at {:.1} min: swarm of {} {}", minute, count, spec.id);
+                let modifiers = scaling_modifiers(&timeline, minute, 1.0, 1.0);
+                let count = (*count as usize).min(free);
+                for i in 0..count {
+                    let angle = i as f32 / count as f32 * PI * 2.0;
+                    let pos = player_pos + Vec2::from_angle(angle) * *radius;
+                    commands.spawn((EnemyBundle::from_spec(spec, *kind, &atlas, pos.extend(1.0)), modifiers.clone()));
+                }
+                free -= count;
+            }
+            WaveEvent::EliteWave { count, health_multiplier, damage_multiplier, scale, .. } => {
+                info!("Wave event at {:.1} min: {} elite {}", minute, count, spec.id);
+                let modifiers = scaling_modifiers(&timeline, minute, *health_multiplier, *damage_multiplier);
+                let count = (*count as usize).min(free);
+                for i in 0..count {
+                    let angle = i as f32 / count as f32 * PI * 2.0;
+                    let pos = player_pos + Vec2::from_angle(angle) * cfg::ELITE_WAVE_RADIUS;
+                    let mut bundle = EnemyBundle::from_spec(spec, *kind, &atlas, pos.extend(1.0));
+                    bundle.transform.scale *= *scale;
+                    // elite waves always roll affixes, whatever the elite chance
+                    let chosen = match affixes.as_deref() {
+                        Some(affixes) => affixes.roll(affix_count(&timeline, minute, rng), rng),
+                        None => Vec::new(),
+                    };
+                    spawn_enemy(&mut commands, bundle, modifiers.clone(), &chosen);
+                }
+                free -= count;
+            }
+            WaveEvent::Boss { .. } => {}
+        }
+    }
+}
+
+fn affix_count(timeline: &ResolvedTimeline, minute: f32, rng: &mut impl Rng) -> usize {
+    let max = timeline.max_affixes.sample(minute).floor().max(1.0) as usize;
+    rng.random_range(1..=max)
+}
+
+// spawns an enemy, promoted to an elite when it rolled any affixes
+fn spawn_enemy(commands: &mut Commands, mut bundle: EnemyBundle, mut modifiers: StatModifiers, affixes: &[&AffixSpec]) {
+    if affixes.is_empty() {
+        commands.spawn((bundle, modifiers));
+        return;
+    }
+    let elite = promote_to_elite(&mut bundle, &mut modifiers, affixes);
+    commands.spawn((bundle, modifiers, elite));
+}
+
+// health and speed scaling from the difficulty curve at `minute`. `health` and
+// `damage` multiply into it here: percent modifiers add up when stats are
+// resolved, so separate modifiers would stack additively.
+fn scaling_modifiers(timeline: &ResolvedTimeline, minute: f32, health: f32, damage: f32) -> StatModifiers {
+    let health = timeline.health_multiplier.sample(minute) * health;
+    let mut modifiers = StatModifiers::default();
+    modifiers.add(ModifierSource::Director, percent(StatKind::Health, health));
+    modifiers.add(ModifierSource::Director, percent(StatKind::MoveSpeed, timeline.speed_multiplier.sample(minute)));
+    if damage != 1.0 {
+        modifiers.add(ModifierSource::Director, percent(StatKind::Damage, damage));
+    }
+    modifiers
+}
+
+// the scaling a regular spawn gets at this point of the run, for enemies
+// spawned outside the director such as boss summons
+pub(crate) fn current_scaling(timeline: &ResolvedTimeline, run_stats: &RunStats) -> StatModifiers {
+    scaling_modifiers(timeline, run_stats.time_survived.elapsed_secs() / 60.0, 1.0, 1.0)
+}
+
+// a multiplier (2.0 = double) as a percent modifier
+fn percent(stat: StatKind, multiplier: f32) -> StatModifier {
+    StatModifier { stat, flat: 0.0, percent: multiplier - 1.0 }
+}
+
+// Enemies past the leash distance are off-screen and can't catch up, so they
+// only cost simulation time. Bosses are never leashed.
+fn leash_stragglers(
+    mut commands: Commands,
+    timeline: Option<Res<ResolvedTimeline>>,
+    mut director: ResMut<WaveDirector>,
+    player_query: Query<&Transform, With<Player>>,
+    mut enemy_query: Query<(Entity, &mut Transform, &mut BehaviourState), (With<Enemy>, Without<Player>, Without<Boss>)>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    let Some(timeline) = timeline else { return };
+    let Ok(player_tf) = player_query.single() else { return };
+    let player_pos = player_tf.translation.truncate();
+    let heading = director
+        .last_player_pos
+        .map(|last| (player_pos - last).normalize_or_zero())
+        .unwrap_or(Vec2::ZERO);
+    director.last_player_pos = Some(player_pos);
+
+    let leash = timeline.leash;
+    let leash_sq = leash.distance * leash.distance;
+    let rng = game_rng.stream(RngStream::EnemySpawns);
+
+    for (entity, mut transform, mut state) in enemy_query.iter_mut() {
+        if transform.translation.truncate().distance_squared(player_pos) <= leash_sq {
+            continue;
+        }
+        match leash.policy {
+            LeashPolicy::Teleport => {
+                let pos = if heading == Vec2::ZERO {
+                    get_random_position_around(player_pos, rng)
+                } else {
+                    // within 60 degrees either side of the player's heading
+                    let angle = heading.to_angle() + rng.random_range(-PI / 3.0..PI / 3.0);
+                    player_pos + Vec2::from_angle(angle) * rng.random_range(cfg::SPAWN_RING_MIN..cfg::SPAWN_RING_MAX)
+                };
+                transform.translation.x = pos.x;
+                transform.translation.y = pos.y;
+                // e.g. don't finish a charge that started on the other side of the map
+                *state = BehaviourState::default();
+            }
+            LeashPolicy::Despawn => {
+                commands.entity(entity).despawn();
+                director.budget += 1.0;
+            }
+        }
+    }
+}
+
+fn get_random_position_around(pos: Vec2, rng: &mut impl Rng) -> Vec2 {
+    let angle = rng.random_range(0.0..PI * 2.0);
+    let dist = rng.random_range(cfg::SPAWN_RING_MIN..cfg::SPAWN_RING_MAX);
+    pos + Vec2::from_angle(angle) * dist
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_keyframes_is_a_constant_one() {
+        let curve = Keyframes::default();
+        assert_eq!(curve.sample(0.0), 1.0);
+        assert_eq!(curve.sample(12.0), 1.0);
+    }
+
+    #[test]
+    fn interpolates_between_keyframes() {
+        let curve = Keyframes(vec![(0.0, 1.0), (10.0, 2.0), (20.0, 4.0)]);
+        assert_eq!(curve.sample(0.0), 1.0);
+        assert_eq!(curve.sample(5.0), 1.5);
+        assert_eq!(curve.sample(10.0), 2.0);
+        assert_eq!(curve.sample(15.0), 3.0);
+    }
+
+    #[test]
+    fn holds_flat_past_either_end() {
+        let curve = Keyframes(vec![(2.0, 3.0), (4.0, 5.0)]);
+        assert_eq!(curve.sample(-1.0), 3.0);
+        assert_eq!(curve.sample(0.0), 3.0);
+        assert_eq!(curve.sample(4.0), 5.0);
+        assert_eq!(curve.sample(60.0), 5.0);
+
+        let single = Keyframes(vec![(5.0, 2.0)]);
+        assert_eq!(single.sample(0.0), 2.0);
+        assert_eq!(single.sample(10.0), 2.0);
+    }
+
+    #[test]
+    fn out_of_order_keyframes_are_not_sorted() {
+        assert!(Keyframes::default().is_sorted());
+        assert!(Keyframes(vec![(0.0, 1.0), (5.0, 1.0), (5.0, 3.0)]).is_sorted());
+        assert!(!Keyframes(vec![(0.0, 1.0), (10.0, 2.0), (5.0, 3.0)]).is_sorted());
+    }
+
+    #[test]
+    fn identical_times_make_a_step() {
+        let curve = Keyframes(vec![(0.0, 1.0), (5.0, 1.0), (5.0, 3.0), (10.0, 3.0)]);
+        assert_eq!(curve.sample(5.0), 1.0);
+        assert_eq!(curve.sample(5.5), 3.0);
+
+        let only_step = Keyframes(vec![(5.0, 1.0), (5.0, 3.0)]);
+        assert_eq!(only_step.sample(5.0), 1.0);
+        assert_eq!(only_step.sample(6.0), 3.0);
+    }
+}
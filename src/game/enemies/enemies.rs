@@ -1,38 +1,30 @@
-use std::f32::consts::PI;
-
 use bevy::ecs::relationship::RelationshipSourceCollection;
-use bevy::math::vec3;
 use bevy::prelude::*;
-use rand::Rng;
 
-use crate::game::animation::animation::{AnimationTimer, AtlasIndex};
 use crate::game::common::components::characters::health::Health;
-use crate::game::enemies::component::Enemy;
-use crate::game::player::component::Player;
-use crate::game::common::components::characters::position::Position;
+use crate::game::common::components::characters::stats::EffectiveStats;
+use crate::game::enemies::component::{Enemy, Knockback};
 use crate::game::game_state::GameState;
-use crate::game::resources::GlobalTextureAtlas;
 use crate::game::config as cfg;
+use crate::game::enemies::behaviour::EnemyBehaviourPlugin;
+use crate::game::enemies::affixes::AffixPlugin;
+use crate::game::enemies::boss::BossPlugin;
+use crate::game::enemies::director::WaveDirectorPlugin;
+use crate::game::enemies::registry::EnemyRegistryPlugin;
+use crate::game::spatial::KDTree2;
 
 pub struct EnemyPlugin;
 
 #[derive(Component, Default)]
 pub struct CollidableEnemy;
 
-#[derive(Component)]
-pub enum EnemyType {
-    Green,
-    Red,
-    Skin,
-}
-
 impl Plugin for EnemyPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
+        app.add_plugins((EnemyRegistryPlugin, EnemyBehaviourPlugin, WaveDirectorPlugin, BossPlugin, AffixPlugin)).add_systems(
             Update,
             (
-                // spawn_enemies.run_if(on_timer(Duration::from_secs_f32(cfg::ENEMY_SPAWN_INTERVAL))),
-                update_enemy_transform,
+                apply_knockback,
+                apply_separation,
                 // despawn_dead_enemies,
             )
                 .run_if(in_state(GameState::InGame)),
@@ -52,106 +44,56 @@ fn despawn_dead_enemies(mut commands: Commands, enemy_query: Query<(&Health, Ent
     }
 }
 
-fn update_enemy_transform(
+fn apply_knockback(
+    mut commands: Commands,
     time: Res<Time>,
-    player_query: Query<&Position, With<Player>>,
-    mut enemy_query: Query<(&mut Transform, &mut Sprite), (With<Enemy>, Without<Player>)>,
+    mut enemy_query: Query<(Entity, &mut Transform, &mut Knockback), With<Enemy>>,
 ) {
-    if player_query.is_empty() || enemy_query.is_empty() {
-        return;
-    }
-
-    let player_pos_comp = if let Ok(p) = player_query.single() { p } else { return };
-    let dt = time.delta().as_secs_f32();
-
-    for (mut transform, mut sprite) in enemy_query.iter_mut() {
-        let enemy_pos2 = transform.translation.truncate();
-        let player_pos2 = Vec2::new(player_pos_comp.x, player_pos_comp.y);
-        let mut dir2 = player_pos2 - enemy_pos2;
-        let len = dir2.length();
-        if len <= std::f32::EPSILON {
-            continue;
-        }
-        dir2 /= len;
-        transform.translation += vec3(dir2.x, dir2.y, 0.0) * (cfg::ENEMY_SPEED * dt);
-
-        // flip sprite to face player horizontally
-        if player_pos2.x > transform.translation.x {
-            sprite.flip_x = false;
-        } else {
-            sprite.flip_x = true;
+    let dt = time.delta_secs();
+    for (entity, mut transform, mut knockback) in enemy_query.iter_mut() {
+        transform.translation += knockback.0.extend(0.0) * dt;
+        knockback.0 *= (-cfg::KNOCKBACK_DECAY * dt).exp();
+        if knockback.0.length_squared() < 1.0 {
+            commands.entity(entity).remove::<Knockback>();
         }
     }
 }
 
-fn spawn_enemies(
-    mut commands: Commands,
-    handle: Res<GlobalTextureAtlas>,
-    player_query: Query<&Transform, With<Player>>,
-    enemy_query: Query<&Transform, (With<Enemy>, Without<Player>)>,
+// Pushes each enemy away from neighbours inside its personal space so crowds
+// spread out instead of stacking on the player. Neighbour positions come from
+// the kd-tree, so they lag by up to KD_TREE_REFRESH_RATE; that is fine for a
+// soft force and keeps the lookup off the hot path.
+fn apply_separation(
+    time: Res<Time>,
+    tree: Res<KDTree2>,
+    mut enemy_query: Query<(Entity, &mut Transform, &EffectiveStats), With<CollidableEnemy>>,
 ) {
-    let num_enemies = enemy_query.iter().len();
-    let enemy_spawn_count = (cfg::MAX_NUM_ENEMIES - num_enemies).min(cfg::SPAWN_RATE_PER_SECOND);
-
-    if num_enemies >= cfg::MAX_NUM_ENEMIES || player_query.is_empty() {
-        return;
-    }
-
-    let player_tf = if let Ok(t) = player_query.single() { t } else { return };
-    let player_pos = player_tf.translation.truncate();
-    for _ in 0..enemy_spawn_count {
-        let (x, y) = get_random_position_around(player_pos);
-        let enemy_type = EnemyType::get_rand_enemy();
-        commands.spawn((
-            Sprite::from_atlas_image(
-                handle.image.clone(),
-                TextureAtlas {
-                    layout: handle.layout.clone(),
-                    index: enemy_type.get_base_sprite_index(),
-                },
-            ),
-            Transform::from_translation(vec3(x, y, 1.0)).with_scale(Vec3::splat(cfg::SPRITE_SCALE as f32)),
-            Enemy::default(),
-            Health::default(),
-            CollidableEnemy::default(),
-            AtlasIndex(0),
-            enemy_type,
-            AnimationTimer(Timer::from_seconds(0.08, TimerMode::Repeating)),
-        ))
-        ;
-    }
-}
-
-fn get_random_position_around(pos: Vec2) -> (f32, f32) {
-    let mut rng = rand::rng();
-    let angle = rng.random_range(0.0..PI * 2.0);
-    let dist = rng.random_range(1000.0..1500.0);
-
-    let offset_x = angle.cos() * dist;
-    let offset_y = angle.sin() * dist;
-
-    let random_x = pos.x + offset_x;
-    let random_y = pos.y + offset_y;
-
-    (random_x, random_y)
-}
-
-impl EnemyType {
-    fn get_rand_enemy() -> Self {
-        let mut rng = rand::rng();
-        let rand_index = rng.random_range(0..3);
-        return match rand_index {
-            0 => Self::Green,
-            1 => Self::Red,
-            _ => Self::Skin,
-        };
-    }
-
-    pub fn get_base_sprite_index(&self) -> usize {
-        match self {
-            EnemyType::Green => 8,
-            EnemyType::Red => 12,
-            EnemyType::Skin => 20,
+    let dt = time.delta_secs();
+    let radius = cfg::ENEMY_SEPARATION_RADIUS;
+
+    enemy_query.par_iter_mut().for_each(|(entity, mut transform, stats)| {
+        let pos = transform.translation.truncate();
+        let mut push = Vec2::ZERO;
+        for (other_pos, other) in tree.within_distance(transform.translation, radius) {
+            if other == Some(entity) {
+                continue;
+            }
+            let away = pos - other_pos.truncate();
+            let dist = away.length();
+            let dir = if dist > f32::EPSILON {
+                away / dist
+            } else {
+                // stacked exactly on top of each other; split them along an
+                // arbitrary but stable direction
+                Vec2::from_angle(entity.index_u32() as f32)
+            };
+            // stronger the deeper the overlap
+            push += dir * (1.0 - dist / radius);
         }
-    }
+        if push == Vec2::ZERO {
+            return;
+        }
+        let push = push.clamp_length_max(1.0) * stats.move_speed * cfg::ENEMY_SEPARATION_WEIGHT;
+        transform.translation += push.extend(0.0) * dt;
+    });
 }
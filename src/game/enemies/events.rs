@@ -0,0 +1,8 @@
+use bevy::prelude::*;
+
+// fired for every enemy whose health reaches zero, just before it is despawned
+#[derive(Event)]
+pub struct EnemyKilledEvent {
+    pub entity: Entity,
+    pub position: Vec3,
+}
@@ -1,3 +1,9 @@
 pub mod enemies;
 pub mod enemies_example;
 pub mod component;
+pub mod events;
+pub mod registry;
+pub mod director;
+pub mod boss;
+pub mod affixes;
+pub mod behaviour;
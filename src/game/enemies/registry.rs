@@ -0,0 +1,119 @@
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use thiserror::Error;
+
+use crate::game::assets::GameAssets;
+use crate::game::config as cfg;
+use crate::game::enemies::enemies_example::{EnemyList, EnemySpec};
+use crate::game::game_state::GameState;
+
+// Index of an enemy spec in the `EnemyRegistry`. Cheap to copy, so it is what
+// enemies carry around instead of their id string.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct EnemyKind(pub usize);
+
+// Every enemy defined in `entities/enemies/devil.ron`, looked up by id. Adding
+// an enemy only takes a new entry in the RON file.
+#[derive(Resource)]
+pub struct EnemyRegistry {
+    specs: Vec<EnemySpec>,
+    // absolute atlas indices to cycle through: idle, then each moving offset
+    frames: Vec<Vec<usize>>,
+    ids: HashMap<String, EnemyKind>,
+}
+
+#[derive(Debug, Error)]
+pub enum EnemyRegistryError {
+    #[error("no enemies defined")]
+    Empty,
+    #[error("enemy id `{0}` is defined more than once")]
+    DuplicateId(String),
+    #[error("enemy `{id}` uses atlas tile {index}, but the atlas only has {len} tiles")]
+    SpriteOutOfRange { id: String, index: usize, len: usize },
+    #[error("enemy `{id}` has a negative spawn_rate {rate}")]
+    NegativeSpawnRate { id: String, rate: f32 },
+    #[error("unknown enemy `{id}` referenced by {referenced_by}")]
+    UnknownEnemy { id: String, referenced_by: String },
+}
+
+impl EnemyRegistry {
+    pub fn from_list(list: &EnemyList) -> Result<Self, EnemyRegistryError> {
+        if list.enemies.is_empty() {
+            return Err(EnemyRegistryError::Empty);
+        }
+
+        let atlas_len = (cfg::SPRITE_SHEET_W * cfg::SPRITE_SHEET_H) as usize;
+        let mut ids = HashMap::default();
+        let mut frames = Vec::with_capacity(list.enemies.len());
+
+        for (i, spec) in list.enemies.iter().enumerate() {
+            if ids.insert(spec.id.clone(), EnemyKind(i)).is_some() {
+                return Err(EnemyRegistryError::DuplicateId(spec.id.clone()));
+            }
+            if spec.metadata.spawn_rate < 0.0 {
+                return Err(EnemyRegistryError::NegativeSpawnRate { id: spec.id.clone(), rate: spec.metadata.spawn_rate });
+            }
+
+            let sprite = &spec.sprite;
+            let cycle: Vec<usize> = std::iter::once(sprite.idle)
+                .chain(sprite.moving.iter().map(|offset| sprite.idle + offset))
+                .collect();
+            if let Some(&index) = cycle.iter().find(|&&index| index >= atlas_len) {
+                return Err(EnemyRegistryError::SpriteOutOfRange { id: spec.id.clone(), index, len: atlas_len });
+            }
+            frames.push(cycle);
+        }
+
+        Ok(EnemyRegistry { specs: list.enemies.clone(), frames, ids })
+    }
+
+    pub fn get(&self, kind: EnemyKind) -> &EnemySpec {
+        &self.specs[kind.0]
+    }
+
+    pub fn frames(&self, kind: EnemyKind) -> &[usize] {
+        &self.frames[kind.0]
+    }
+
+    pub fn kind(&self, id: &str) -> Option<EnemyKind> {
+        self.ids.get(id).copied()
+    }
+
+    // for data that points at enemies by id (spawn tables, bosses, ...), so a
+    // typo is reported while loading instead of silently spawning nothing
+    pub fn resolve(&self, id: &str, referenced_by: &str) -> Result<EnemyKind, EnemyRegistryError> {
+        self.kind(id).ok_or_else(|| EnemyRegistryError::UnknownEnemy {
+            id: id.to_string(),
+            referenced_by: referenced_by.to_string(),
+        })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (EnemyKind, &EnemySpec)> {
+        self.specs.iter().enumerate().map(|(i, spec)| (EnemyKind(i), spec))
+    }
+}
+
+pub struct EnemyRegistryPlugin;
+
+impl Plugin for EnemyRegistryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnExit(GameState::Loading), build_registry);
+    }
+}
+
+// an invalid enemy file leaves the registry missing, so nothing spawns and the
+// error is the first thing in the log
+pub(crate) fn build_registry(mut commands: Commands, game_assets: Res<GameAssets>, enemy_lists: Res<Assets<EnemyList>>) {
+    let Some(list) = enemy_lists.get(&game_assets.enemy_list) else {
+        error!("Enemy list entities/enemies/devil.ron is not loaded");
+        return;
+    };
+
+    match EnemyRegistry::from_list(list) {
+        Ok(registry) => {
+            info!("Loaded {} enemy kinds", registry.specs.len());
+            commands.insert_resource(registry);
+        }
+        Err(e) => error!("Invalid enemy data in entities/enemies/devil.ron: {e}"),
+    }
+}
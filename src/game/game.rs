@@ -5,16 +5,28 @@ use crate::game::enemies::enemies_example::EnemyAssetsExamplePlugin;
 // use crate::game::map::terrain::TerrainPlugin;
 use crate::game::player::player::PlayerPlugin;
 use crate::game::common::components::characters::health::HealthPlugin;
-// use crate::game::map::map::MapPlugin;
+use crate::game::common::components::characters::stats::StatsPlugin;
+use crate::game::map::map::MapPlugin;
+use crate::game::map::navigation::NavigationPlugin;
 use crate::game::camera::camera::CameraPlugin;
 use crate::game::resources::ResourcesPlugin;
 use crate::game::collisions::collisions::CollisionPlugin;
+use crate::game::input::input::InputActionsPlugin;
+use crate::game::run::RunPlugin;
+use crate::game::time_scale::TimeScalePlugin;
+use crate::game::rng::RngPlugin;
+use crate::game::assets::GameAssetsPlugin;
+use crate::game::ui::game_over::GameOverPlugin;
+use crate::game::ui::{boss_bar::BossBarPlugin, level_up::LevelUpPlugin, xp_bar::XpBarPlugin};
+use crate::game::progression::{experience::ExperiencePlugin, loot::LootPlugin, upgrades::UpgradesPlugin};
 
 pub struct GamePlugin;
 
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
         app
-            .add_plugins((ResourcesPlugin, HealthPlugin, PlayerPlugin, CameraPlugin, EnemyPlugin, CollisionPlugin, EnemyAssetsExamplePlugin));
+            .add_plugins((ResourcesPlugin, InputActionsPlugin, HealthPlugin, PlayerPlugin, CameraPlugin, EnemyPlugin, CollisionPlugin, EnemyAssetsExamplePlugin))
+            .add_plugins((GameAssetsPlugin, StatsPlugin, RunPlugin, GameOverPlugin, TimeScalePlugin, RngPlugin, MapPlugin, NavigationPlugin))
+            .add_plugins((ExperiencePlugin, LootPlugin, UpgradesPlugin, LevelUpPlugin, XpBarPlugin, BossBarPlugin));
     }
 }
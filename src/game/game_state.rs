@@ -8,5 +8,6 @@ pub enum GameState {
     MainMenu,
     InGame,
     Paused,
+    LevelUp,
     GameOver,
 }
@@ -23,13 +23,19 @@ use bevy::{
     platform::collections::HashMap,
     prelude::{
         Added, Asset, AssetApp, AssetEvent, AssetId, Assets, Bundle, Commands, Component, Entity,
-        GlobalTransform, Handle, Image, MessageReader, Plugin, Query, Res, Transform, Update,
+        GlobalTransform, Handle, Image, MessageReader, Plugin, Query, Rect, Res, ResMut, Transform,
+        Update, Vec2,
     },
     reflect::TypePath,
 };
 use bevy_ecs_tilemap::prelude::*;
 use thiserror::Error;
 
+use crate::game::map::navigation::NavGrid;
+
+// tiles on a layer with this name block enemy pathfinding
+const WALL_LAYER_NAME: &str = "walls";
+
 #[allow(dead_code)]
 #[derive(Default)]
 pub struct TiledMapPlugin;
@@ -220,6 +226,7 @@ fn process_loaded_maps(
         &TilemapRenderSettings,
     )>,
     new_maps: Query<&TiledMapHandle, Added<TiledMapHandle>>,
+    mut nav_grid: Option<ResMut<NavGrid>>,
 ) {
     let mut changed_maps = Vec::<AssetId<TiledMap>>::default();
     for event in map_events.read() {
@@ -332,6 +339,7 @@ fn process_loaded_maps(
 
                         let mut tile_storage = TileStorage::empty(map_size);
                         let layer_entity = commands.spawn_empty().id();
+                        let is_wall_layer = layer.name == WALL_LAYER_NAME;
 
                         for x in 0..map_size.x {
                             for y in 0..map_size.y {
@@ -383,6 +391,18 @@ fn process_loaded_maps(
                                     })
                                     .id();
                                 tile_storage.set(&tile_pos, tile_entity);
+
+                                if is_wall_layer && let Some(nav_grid) = nav_grid.as_mut() {
+                                    // the layer is anchored at its centre
+                                    let min = Vec2::new(
+                                        offset_x + (x as f32 - map_size.x as f32 / 2.0) * grid_size.x,
+                                        -offset_y + (y as f32 - map_size.y as f32 / 2.0) * grid_size.y,
+                                    );
+                                    nav_grid.block_rect(Rect::from_corners(
+                                        min,
+                                        min + Vec2::new(grid_size.x, grid_size.y),
+                                    ));
+                                }
                             }
                         }
 
@@ -394,7 +414,8 @@ fn process_loaded_maps(
                             tile_size,
                             spacing: tile_spacing,
                             anchor: TilemapAnchor::Center,
-                            transform: Transform::from_xyz(offset_x, -offset_y, layer_index as f32),
+                            // layers stack below pickups (z 0.5) and characters (z 1)
+                            transform: Transform::from_xyz(offset_x, -offset_y, layer_index as f32 * 0.1),
                             map_type,
                             render_settings: *render_settings,
                             ..Default::default()
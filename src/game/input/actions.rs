@@ -0,0 +1,94 @@
+use bevy::platform::collections::HashSet;
+use bevy::prelude::*;
+use serde::Deserialize;
+
+// Logical inputs read by gameplay systems. Physical keys/buttons are mapped to
+// these through `assets/input/bindings.ron`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Reflect)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Fire,
+    Pause,
+    Quit,
+    Restart,
+    Confirm,
+    NextWeapon,
+    PrevWeapon,
+    // 1-based inventory slot, e.g. `WeaponSlot(1)` for the first weapon
+    WeaponSlot(u8),
+    ToggleAutoAim,
+}
+
+// Per-frame snapshot of which actions are held / were pressed this frame,
+// plus the analog stick readings (already past their dead zones).
+#[derive(Resource, Default)]
+pub struct ActionState {
+    pressed: HashSet<Action>,
+    just_pressed: HashSet<Action>,
+    move_stick: Vec2,
+    aim_stick: Option<Vec2>,
+}
+
+impl ActionState {
+    pub fn pressed(&self, action: Action) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.just_pressed.contains(&action)
+    }
+
+    // digital movement axis built from the four move actions (not normalized)
+    pub fn movement(&self) -> Vec2 {
+        let mut dir = Vec2::ZERO;
+        if self.pressed(Action::MoveRight) {
+            dir.x += 1.0;
+        }
+        if self.pressed(Action::MoveLeft) {
+            dir.x -= 1.0;
+        }
+        if self.pressed(Action::MoveUp) {
+            dir.y += 1.0;
+        }
+        if self.pressed(Action::MoveDown) {
+            dir.y -= 1.0;
+        }
+        dir
+    }
+
+    // movement with analog magnitude in 0..=1: the stick wins when it is
+    // deflected, otherwise the digital direction is normalized
+    pub fn move_axis(&self) -> Vec2 {
+        if self.move_stick != Vec2::ZERO {
+            return self.move_stick.clamp_length_max(1.0);
+        }
+        self.movement().normalize_or_zero()
+    }
+
+    // unit aim direction from the right stick; `None` means aim with the mouse
+    pub fn aim_direction(&self) -> Option<Vec2> {
+        self.aim_stick
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.pressed.clear();
+        self.just_pressed.clear();
+        self.move_stick = Vec2::ZERO;
+        self.aim_stick = None;
+    }
+
+    pub(crate) fn set_sticks(&mut self, move_stick: Vec2, aim_stick: Option<Vec2>) {
+        self.move_stick = move_stick;
+        self.aim_stick = aim_stick;
+    }
+
+    pub(crate) fn press(&mut self, action: Action, just_pressed: bool) {
+        self.pressed.insert(action);
+        if just_pressed {
+            self.just_pressed.insert(action);
+        }
+    }
+}
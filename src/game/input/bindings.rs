@@ -0,0 +1,326 @@
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::game::input::actions::Action;
+
+// Raw bindings file as written by designers, e.g. `assets/input/bindings.ron`.
+// Names are kept as strings here and resolved into an `InputMap` after loading
+// so typos and conflicts can be reported with a readable message.
+#[derive(Deserialize, Asset, TypePath)]
+pub struct InputBindings {
+    pub bindings: Vec<ActionBinding>,
+    #[serde(default)]
+    pub dead_zones: StickDeadZones,
+}
+
+// Stick dead zones, as a fraction of full deflection. Input below the dead
+// zone is ignored and the remaining range is rescaled to 0..1.
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct StickDeadZones {
+    pub movement: f32,
+    pub aim: f32,
+}
+
+impl Default for StickDeadZones {
+    fn default() -> Self {
+        StickDeadZones {
+            movement: 0.15,
+            aim: 0.25,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ActionBinding {
+    pub action: Action,
+    pub inputs: Vec<InputSource>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub enum InputSource {
+    Key(String),
+    Mouse(String),
+    Gamepad(String),
+    Scroll(String),
+}
+
+// A resolved physical input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BoundInput {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButton),
+    Scroll(ScrollDirection),
+}
+
+// Mouse wheel notches; counts as pressed for the frame the wheel moved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScrollDirection {
+    Up,
+    Down,
+}
+
+#[derive(Debug, Error)]
+pub enum BindingError {
+    #[error("unknown key name `{name}` bound to {action:?}")]
+    UnknownKey { name: String, action: Action },
+    #[error("unknown mouse button `{name}` bound to {action:?} (expected Left, Right, Middle, Back or Forward)")]
+    UnknownMouseButton { name: String, action: Action },
+    #[error("unknown gamepad button `{name}` bound to {action:?}")]
+    UnknownGamepadButton { name: String, action: Action },
+    #[error("unknown scroll direction `{name}` bound to {action:?} (expected Up or Down)")]
+    UnknownScrollDirection { name: String, action: Action },
+    #[error("gamepad dead zone {0} must be between 0.0 and 1.0 (exclusive)")]
+    InvalidDeadZone(f32),
+    #[error("{input:?} is bound to both {first:?} and {second:?}")]
+    Conflict { input: BoundInput, first: Action, second: Action },
+    #[error("{0:?} is listed more than once")]
+    DuplicateAction(Action),
+}
+
+// Active action -> inputs mapping used by `update_action_state`.
+#[derive(Resource, Debug)]
+pub struct InputMap {
+    bindings: HashMap<Action, Vec<BoundInput>>,
+    pub dead_zones: StickDeadZones,
+}
+
+impl Default for InputMap {
+    // mirrors `assets/input/bindings.ron` so the game is playable before it loads
+    fn default() -> Self {
+        let bindings = [
+            (Action::MoveUp, vec![BoundInput::Key(KeyCode::KeyW), BoundInput::Key(KeyCode::ArrowUp), BoundInput::Gamepad(GamepadButton::DPadUp)]),
+            (Action::MoveDown, vec![BoundInput::Key(KeyCode::KeyS), BoundInput::Key(KeyCode::ArrowDown), BoundInput::Gamepad(GamepadButton::DPadDown)]),
+            (Action::MoveLeft, vec![BoundInput::Key(KeyCode::KeyA), BoundInput::Key(KeyCode::ArrowLeft), BoundInput::Gamepad(GamepadButton::DPadLeft)]),
+            (Action::MoveRight, vec![BoundInput::Key(KeyCode::KeyD), BoundInput::Key(KeyCode::ArrowRight), BoundInput::Gamepad(GamepadButton::DPadRight)]),
+            (Action::Fire, vec![BoundInput::Mouse(MouseButton::Left), BoundInput::Gamepad(GamepadButton::RightTrigger2)]),
+            (Action::Pause, vec![BoundInput::Key(KeyCode::KeyP), BoundInput::Gamepad(GamepadButton::Start)]),
+            (Action::Quit, vec![BoundInput::Key(KeyCode::Escape)]),
+            (Action::Restart, vec![BoundInput::Key(KeyCode::KeyR), BoundInput::Gamepad(GamepadButton::Select)]),
+            (Action::Confirm, vec![BoundInput::Key(KeyCode::Enter), BoundInput::Key(KeyCode::Space), BoundInput::Gamepad(GamepadButton::South)]),
+            (Action::NextWeapon, vec![BoundInput::Scroll(ScrollDirection::Down), BoundInput::Key(KeyCode::KeyE), BoundInput::Gamepad(GamepadButton::RightTrigger)]),
+            (Action::PrevWeapon, vec![BoundInput::Scroll(ScrollDirection::Up), BoundInput::Key(KeyCode::KeyQ), BoundInput::Gamepad(GamepadButton::LeftTrigger)]),
+            (Action::WeaponSlot(1), vec![BoundInput::Key(KeyCode::Digit1)]),
+            (Action::WeaponSlot(2), vec![BoundInput::Key(KeyCode::Digit2)]),
+            (Action::WeaponSlot(3), vec![BoundInput::Key(KeyCode::Digit3)]),
+            (Action::WeaponSlot(4), vec![BoundInput::Key(KeyCode::Digit4)]),
+            (Action::WeaponSlot(5), vec![BoundInput::Key(KeyCode::Digit5)]),
+            (Action::ToggleAutoAim, vec![BoundInput::Key(KeyCode::KeyT), BoundInput::Gamepad(GamepadButton::North)]),
+        ];
+        InputMap {
+            bindings: bindings.into_iter().collect(),
+            dead_zones: StickDeadZones::default(),
+        }
+    }
+}
+
+impl InputMap {
+    pub fn from_bindings(file: &InputBindings) -> Result<Self, BindingError> {
+        for dead_zone in [file.dead_zones.movement, file.dead_zones.aim] {
+            if !(0.0..1.0).contains(&dead_zone) {
+                return Err(BindingError::InvalidDeadZone(dead_zone));
+            }
+        }
+
+        let mut bindings: HashMap<Action, Vec<BoundInput>> = HashMap::default();
+        let mut owners: HashMap<BoundInput, Action> = HashMap::default();
+
+        for binding in &file.bindings {
+            if bindings.contains_key(&binding.action) {
+                return Err(BindingError::DuplicateAction(binding.action));
+            }
+
+            let mut inputs = Vec::with_capacity(binding.inputs.len());
+            for source in &binding.inputs {
+                let input = source.resolve(binding.action)?;
+                if let Some(&first) = owners.get(&input) {
+                    return Err(BindingError::Conflict { input, first, second: binding.action });
+                }
+                owners.insert(input, binding.action);
+                inputs.push(input);
+            }
+            bindings.insert(binding.action, inputs);
+        }
+
+        Ok(InputMap { bindings, dead_zones: file.dead_zones })
+    }
+
+    pub fn inputs(&self, action: Action) -> &[BoundInput] {
+        self.bindings.get(&action).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Action, &[BoundInput])> {
+        self.bindings.iter().map(|(action, inputs)| (*action, inputs.as_slice()))
+    }
+}
+
+impl BoundInput {
+    // short human readable name, used for on-screen prompts
+    pub fn label(&self) -> String {
+        match self {
+            BoundInput::Key(key) => format!("{key:?}").trim_start_matches("Key").to_string(),
+            BoundInput::Mouse(button) => format!("Mouse {button:?}"),
+            BoundInput::Gamepad(button) => format!("Pad {button:?}"),
+            BoundInput::Scroll(direction) => format!("Scroll {direction:?}"),
+        }
+    }
+}
+
+impl InputSource {
+    fn resolve(&self, action: Action) -> Result<BoundInput, BindingError> {
+        match self {
+            InputSource::Key(name) => parse_key(name)
+                .map(BoundInput::Key)
+                .ok_or_else(|| BindingError::UnknownKey { name: name.clone(), action }),
+            InputSource::Mouse(name) => parse_mouse_button(name)
+                .map(BoundInput::Mouse)
+                .ok_or_else(|| BindingError::UnknownMouseButton { name: name.clone(), action }),
+            InputSource::Gamepad(name) => parse_gamepad_button(name)
+                .map(BoundInput::Gamepad)
+                .ok_or_else(|| BindingError::UnknownGamepadButton { name: name.clone(), action }),
+            InputSource::Scroll(name) => parse_scroll_direction(name)
+                .map(BoundInput::Scroll)
+                .ok_or_else(|| BindingError::UnknownScrollDirection { name: name.clone(), action }),
+        }
+    }
+}
+
+fn parse_mouse_button(name: &str) -> Option<MouseButton> {
+    match name {
+        "Left" => Some(MouseButton::Left),
+        "Right" => Some(MouseButton::Right),
+        "Middle" => Some(MouseButton::Middle),
+        "Back" => Some(MouseButton::Back),
+        "Forward" => Some(MouseButton::Forward),
+        _ => None,
+    }
+}
+
+fn parse_scroll_direction(name: &str) -> Option<ScrollDirection> {
+    match name {
+        "Up" => Some(ScrollDirection::Up),
+        "Down" => Some(ScrollDirection::Down),
+        _ => None,
+    }
+}
+
+// gamepad button names follow bevy's `GamepadButton` variant names
+fn parse_gamepad_button(name: &str) -> Option<GamepadButton> {
+    GAMEPAD_BUTTON_NAMES.iter().find(|(n, _)| *n == name).map(|(_, b)| *b)
+}
+
+// key names follow bevy's `KeyCode` variant names
+fn parse_key(name: &str) -> Option<KeyCode> {
+    KEY_NAMES.iter().find(|(n, _)| *n == name).map(|(_, k)| *k)
+}
+
+const KEY_NAMES: &[(&str, KeyCode)] = &[
+    ("KeyA", KeyCode::KeyA),
+    ("KeyB", KeyCode::KeyB),
+    ("KeyC", KeyCode::KeyC),
+    ("KeyD", KeyCode::KeyD),
+    ("KeyE", KeyCode::KeyE),
+    ("KeyF", KeyCode::KeyF),
+    ("KeyG", KeyCode::KeyG),
+    ("KeyH", KeyCode::KeyH),
+    ("KeyI", KeyCode::KeyI),
+    ("KeyJ", KeyCode::KeyJ),
+    ("KeyK", KeyCode::KeyK),
+    ("KeyL", KeyCode::KeyL),
+    ("KeyM", KeyCode::KeyM),
+    ("KeyN", KeyCode::KeyN),
+    ("KeyO", KeyCode::KeyO),
+    ("KeyP", KeyCode::KeyP),
+    ("KeyQ", KeyCode::KeyQ),
+    ("KeyR", KeyCode::KeyR),
+    ("KeyS", KeyCode::KeyS),
+    ("KeyT", KeyCode::KeyT),
+    ("KeyU", KeyCode::KeyU),
+    ("KeyV", KeyCode::KeyV),
+    ("KeyW", KeyCode::KeyW),
+    ("KeyX", KeyCode::KeyX),
+    ("KeyY", KeyCode::KeyY),
+    ("KeyZ", KeyCode::KeyZ),
+    ("Digit0", KeyCode::Digit0),
+    ("Digit1", KeyCode::Digit1),
+    ("Digit2", KeyCode::Digit2),
+    ("Digit3", KeyCode::Digit3),
+    ("Digit4", KeyCode::Digit4),
+    ("Digit5", KeyCode::Digit5),
+    ("Digit6", KeyCode::Digit6),
+    ("Digit7", KeyCode::Digit7),
+    ("Digit8", KeyCode::Digit8),
+    ("Digit9", KeyCode::Digit9),
+    ("ArrowUp", KeyCode::ArrowUp),
+    ("ArrowDown", KeyCode::ArrowDown),
+    ("ArrowLeft", KeyCode::ArrowLeft),
+    ("ArrowRight", KeyCode::ArrowRight),
+    ("Space", KeyCode::Space),
+    ("Enter", KeyCode::Enter),
+    ("Escape", KeyCode::Escape),
+    ("Tab", KeyCode::Tab),
+    ("Backspace", KeyCode::Backspace),
+    ("ShiftLeft", KeyCode::ShiftLeft),
+    ("ShiftRight", KeyCode::ShiftRight),
+    ("ControlLeft", KeyCode::ControlLeft),
+    ("ControlRight", KeyCode::ControlRight),
+    ("AltLeft", KeyCode::AltLeft),
+    ("AltRight", KeyCode::AltRight),
+    ("Minus", KeyCode::Minus),
+    ("Equal", KeyCode::Equal),
+    ("BracketLeft", KeyCode::BracketLeft),
+    ("BracketRight", KeyCode::BracketRight),
+    ("Semicolon", KeyCode::Semicolon),
+    ("Quote", KeyCode::Quote),
+    ("Comma", KeyCode::Comma),
+    ("Period", KeyCode::Period),
+    ("Slash", KeyCode::Slash),
+    ("Backslash", KeyCode::Backslash),
+    ("Backquote", KeyCode::Backquote),
+    ("F1", KeyCode::F1),
+    ("F2", KeyCode::F2),
+    ("F3", KeyCode::F3),
+    ("F4", KeyCode::F4),
+    ("F5", KeyCode::F5),
+    ("F6", KeyCode::F6),
+    ("F7", KeyCode::F7),
+    ("F8", KeyCode::F8),
+    ("F9", KeyCode::F9),
+    ("F10", KeyCode::F10),
+    ("F11", KeyCode::F11),
+    ("F12", KeyCode::F12),
+    ("Numpad0", KeyCode::Numpad0),
+    ("Numpad1", KeyCode::Numpad1),
+    ("Numpad2", KeyCode::Numpad2),
+    ("Numpad3", KeyCode::Numpad3),
+    ("Numpad4", KeyCode::Numpad4),
+    ("Numpad5", KeyCode::Numpad5),
+    ("Numpad6", KeyCode::Numpad6),
+    ("Numpad7", KeyCode::Numpad7),
+    ("Numpad8", KeyCode::Numpad8),
+    ("Numpad9", KeyCode::Numpad9),
+];
+
+const GAMEPAD_BUTTON_NAMES: &[(&str, GamepadButton)] = &[
+    ("South", GamepadButton::South),
+    ("East", GamepadButton::East),
+    ("North", GamepadButton::North),
+    ("West", GamepadButton::West),
+    ("C", GamepadButton::C),
+    ("Z", GamepadButton::Z),
+    ("LeftTrigger", GamepadButton::LeftTrigger),
+    ("LeftTrigger2", GamepadButton::LeftTrigger2),
+    ("RightTrigger", GamepadButton::RightTrigger),
+    ("RightTrigger2", GamepadButton::RightTrigger2),
+    ("Select", GamepadButton::Select),
+    ("Start", GamepadButton::Start),
+    ("Mode", GamepadButton::Mode),
+    ("LeftThumb", GamepadButton::LeftThumb),
+    ("RightThumb", GamepadButton::RightThumb),
+    ("DPadUp", GamepadButton::DPadUp),
+    ("DPadDown", GamepadButton::DPadDown),
+    ("DPadLeft", GamepadButton::DPadLeft),
+    ("DPadRight", GamepadButton::DPadRight),
+];
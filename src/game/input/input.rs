@@ -0,0 +1,121 @@
+use bevy::input::InputSystems;
+use bevy::input::mouse::AccumulatedMouseScroll;
+use bevy::prelude::*;
+use bevy_common_assets::ron::RonAssetPlugin;
+
+use crate::game::input::actions::ActionState;
+use crate::game::input::bindings::{BoundInput, InputBindings, InputMap, ScrollDirection};
+
+pub struct InputActionsPlugin;
+
+#[derive(Resource)]
+pub struct InputBindingsHandle(pub Handle<InputBindings>);
+
+impl Plugin for InputActionsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_plugins(RonAssetPlugin::<InputBindings>::new(&["bindings.ron"]))
+            .init_resource::<InputMap>()
+            .init_resource::<ActionState>()
+            .add_systems(Startup, setup)
+            .add_systems(
+                PreUpdate,
+                (apply_loaded_bindings, update_action_state)
+                    .chain()
+                    .after(InputSystems),
+            );
+    }
+}
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let handle: Handle<InputBindings> = asset_server.load("input/bindings.ron");
+    commands.insert_resource(InputBindingsHandle(handle));
+}
+
+// swap in the bindings file once it is loaded (and again on hot reload);
+// invalid files are rejected and the previous map stays active
+fn apply_loaded_bindings(
+    mut asset_events: MessageReader<AssetEvent<InputBindings>>,
+    handle: Option<Res<InputBindingsHandle>>,
+    bindings_assets: Res<Assets<InputBindings>>,
+    mut input_map: ResMut<InputMap>,
+) {
+    let Some(handle) = handle else { return };
+
+    for event in asset_events.read() {
+        if !event.is_loaded_with_dependencies(&handle.0) && !event.is_modified(&handle.0) {
+            continue;
+        }
+        let Some(file) = bindings_assets.get(&handle.0) else { continue };
+
+        match InputMap::from_bindings(file) {
+            Ok(map) => {
+                info!("Loaded input bindings");
+                *input_map = map;
+            }
+            Err(e) => error!("Invalid input bindings in input/bindings.ron: {e}"),
+        }
+    }
+}
+
+fn update_action_state(
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    scroll: Res<AccumulatedMouseScroll>,
+    gamepads: Query<&Gamepad>,
+    input_map: Res<InputMap>,
+    mut actions: ResMut<ActionState>,
+) {
+    actions.clear();
+
+    for (action, inputs) in input_map.iter() {
+        for input in inputs {
+            let (pressed, just_pressed) = match *input {
+                BoundInput::Key(key) => (keys.pressed(key), keys.just_pressed(key)),
+                BoundInput::Mouse(button) => (mouse.pressed(button), mouse.just_pressed(button)),
+                BoundInput::Gamepad(button) => (
+                    gamepads.iter().any(|g| g.pressed(button)),
+                    gamepads.iter().any(|g| g.just_pressed(button)),
+                ),
+                BoundInput::Scroll(direction) => {
+                    let moved = match direction {
+                        ScrollDirection::Up => scroll.delta.y > 0.0,
+                        ScrollDirection::Down => scroll.delta.y < 0.0,
+                    };
+                    (moved, moved)
+                }
+            };
+            if pressed || just_pressed {
+                actions.press(action, just_pressed);
+            }
+        }
+    }
+
+    // movement follows whichever pad is pushed hardest; aim takes the first
+    // right stick outside its dead zone
+    let dead_zones = input_map.dead_zones;
+    let mut move_stick = Vec2::ZERO;
+    let mut aim_stick: Option<Vec2> = None;
+    for gamepad in gamepads.iter() {
+        let left = apply_dead_zone(gamepad.left_stick(), dead_zones.movement);
+        if left.length_squared() > move_stick.length_squared() {
+            move_stick = left;
+        }
+
+        let right = apply_dead_zone(gamepad.right_stick(), dead_zones.aim);
+        if right != Vec2::ZERO && aim_stick.is_none() {
+            aim_stick = Some(right.normalize());
+        }
+    }
+    actions.set_sticks(move_stick, aim_stick);
+}
+
+// radial dead zone, rescaled so output still starts at 0 just past the edge
+fn apply_dead_zone(stick: Vec2, dead_zone: f32) -> Vec2 {
+    let len = stick.length();
+    if len <= dead_zone {
+        return Vec2::ZERO;
+    }
+    let scaled = ((len - dead_zone) / (1.0 - dead_zone)).min(1.0);
+    stick / len * scaled
+}
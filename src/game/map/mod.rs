@@ -1,2 +1,3 @@
 pub mod map;
+pub mod navigation;
 // pub mod terrain;
@@ -0,0 +1,332 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use bevy::platform::collections::HashSet;
+use bevy::prelude::*;
+
+use crate::game::config as cfg;
+use crate::game::game_state::GameState;
+use crate::game::player::component::Player;
+
+// Walkability of the world on a fixed grid. Map sources mark cells as blocked;
+// everything else is walkable. The source is the Tiled loader's "walls" layer
+// (maps/map1.tmx); procedural terrain doesn't block anything yet. Blocked cells
+// stop the player too, see `controls`.
+#[derive(Resource)]
+pub struct NavGrid {
+    cell_size: f32,
+    blocked: HashSet<IVec2>,
+}
+
+impl Default for NavGrid {
+    fn default() -> Self {
+        NavGrid { cell_size: cfg::NAV_CELL_SIZE, blocked: HashSet::default() }
+    }
+}
+
+impl NavGrid {
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    pub fn cell_of(&self, pos: Vec2) -> IVec2 {
+        (pos / self.cell_size).floor().as_ivec2()
+    }
+
+    pub fn is_walkable(&self, cell: IVec2) -> bool {
+        !self.blocked.contains(&cell)
+    }
+
+    // blocks every cell overlapping the given world-space rectangle
+    pub fn block_rect(&mut self, rect: Rect) {
+        let min = self.cell_of(rect.min);
+        let max = self.cell_of(rect.max - Vec2::splat(0.001));
+        for x in min.x..=max.x {
+            for y in min.y..=max.y {
+                self.blocked.insert(IVec2::new(x, y));
+            }
+        }
+    }
+
+    pub fn has_obstacles(&self) -> bool {
+        !self.blocked.is_empty()
+    }
+}
+
+// Direction towards the player for every cell in a square window centred on
+// the player's cell. Enemies sample it in O(1); cells outside the window, walls
+// and unreachable cells have no direction and callers fall back to a straight
+// line.
+#[derive(Resource, Default)]
+pub struct FlowField {
+    origin: IVec2,
+    size: i32,
+    cell_size: f32,
+    directions: Vec<Vec2>,
+}
+
+impl FlowField {
+    pub fn direction_at(&self, pos: Vec2) -> Option<Vec2> {
+        if self.directions.is_empty() {
+            return None;
+        }
+        let local = (pos / self.cell_size).floor().as_ivec2() - self.origin;
+        if local.x < 0 || local.y < 0 || local.x >= self.size || local.y >= self.size {
+            return None;
+        }
+        let dir = self.directions[(local.y * self.size + local.x) as usize];
+        (dir != Vec2::ZERO).then_some(dir)
+    }
+
+    fn clear(&mut self) {
+        self.directions.clear();
+    }
+}
+
+// A Dijkstra search in progress. It is spread over several frames so a
+// recompute never costs more than FLOW_FIELD_BUDGET cell expansions per frame;
+// the finished costs are turned into directions and swapped into `FlowField`.
+#[derive(Resource, Default)]
+struct FlowFieldSearch {
+    active: bool,
+    goal: IVec2,
+    origin: IVec2,
+    costs: Vec<u32>,
+    open: BinaryHeap<Reverse<(u32, u32)>>,
+    // goal of the last finished search
+    solved_goal: Option<IVec2>,
+    // the grid changed since the current search started
+    stale: bool,
+}
+
+// orthogonal steps cost 10, diagonal ones 14 (~10 * sqrt 2)
+const NEIGHBOURS: [(IVec2, u32); 8] = [
+    (IVec2::new(1, 0), 10),
+    (IVec2::new(-1, 0), 10),
+    (IVec2::new(0, 1), 10),
+    (IVec2::new(0, -1), 10),
+    (IVec2::new(1, 1), 14),
+    (IVec2::new(1, -1), 14),
+    (IVec2::new(-1, 1), 14),
+    (IVec2::new(-1, -1), 14),
+];
+
+pub struct NavigationPlugin;
+
+impl Plugin for NavigationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<NavGrid>()
+            .init_resource::<FlowField>()
+            .init_resource::<FlowFieldSearch>()
+            .add_systems(Update, update_flow_field.run_if(in_state(GameState::InGame)));
+    }
+}
+
+fn update_flow_field(
+    grid: Res<NavGrid>,
+    mut field: ResMut<FlowField>,
+    mut search: ResMut<FlowFieldSearch>,
+    player_query: Query<&Transform, With<Player>>,
+) {
+    // open ground needs no pathfinding; straight lines are already optimal
+    if !grid.has_obstacles() {
+        field.clear();
+        search.active = false;
+        search.solved_goal = None;
+        return;
+    }
+    let Ok(player_tf) = player_query.single() else { return };
+    let player_cell = grid.cell_of(player_tf.translation.truncate());
+
+    if grid.is_changed() {
+        search.stale = true;
+    }
+    if !search.active && (search.solved_goal != Some(player_cell) || search.stale) {
+        start_search(&mut search, player_cell);
+    }
+    if !search.active {
+        return;
+    }
+
+    if expand(&mut search, &grid, cfg::FLOW_FIELD_BUDGET) {
+        bake_directions(&search, &grid, &mut field);
+        search.active = false;
+        search.solved_goal = Some(search.goal);
+    }
+}
+
+fn start_search(search: &mut FlowFieldSearch, goal: IVec2) {
+    let size = (cfg::FLOW_FIELD_RADIUS * 2 + 1) as usize;
+    search.active = true;
+    search.stale = false;
+    search.goal = goal;
+    search.origin = goal - IVec2::splat(cfg::FLOW_FIELD_RADIUS);
+    search.costs.clear();
+    search.costs.resize(size * size, u32::MAX);
+    search.open.clear();
+
+    let start = index(search.origin, goal).unwrap();
+    search.costs[start] = 0;
+    search.open.push(Reverse((0, start as u32)));
+}
+
+// runs up to `budget` expansions; returns true once the search is exhausted
+fn expand(search: &mut FlowFieldSearch, grid: &NavGrid, budget: usize) -> bool {
+    for _ in 0..budget {
+        let Some(Reverse((cost, idx))) = search.open.pop() else { return true };
+        if cost > search.costs[idx as usize] {
+            // stale heap entry, a cheaper path was found after it was pushed
+            continue;
+        }
+        let cell = cell_at(search.origin, idx as usize);
+        for (step, step_cost) in NEIGHBOURS {
+            let next = cell + step;
+            let Some(next_idx) = index(search.origin, next) else { continue };
+            if !grid.is_walkable(next) || cuts_corner(grid, cell, step) {
+                continue;
+            }
+            let next_cost = cost + step_cost;
+            if next_cost < search.costs[next_idx] {
+                search.costs[next_idx] = next_cost;
+                search.open.push(Reverse((next_cost, next_idx as u32)));
+            }
+        }
+    }
+    search.open.is_empty()
+}
+
+fn bake_directions(search: &FlowFieldSearch, grid: &NavGrid, field: &mut FlowField) {
+    field.origin = search.origin;
+    field.size = cfg::FLOW_FIELD_RADIUS * 2 + 1;
+    field.cell_size = grid.cell_size();
+    field.directions.clear();
+    field.directions.resize(search.costs.len(), Vec2::ZERO);
+
+    for (idx, &cost) in search.costs.iter().enumerate() {
+        // the goal cell and unreachable cells keep no direction
+        if cost == 0 || cost == u32::MAX {
+            continue;
+        }
+        let cell = cell_at(search.origin, idx);
+        let mut best = (cost, IVec2::ZERO);
+        for (step, _) in NEIGHBOURS {
+            let Some(next_idx) = index(search.origin, cell + step) else { continue };
+            if cuts_corner(grid, cell, step) {
+                continue;
+            }
+            if search.costs[next_idx] < best.0 {
+                best = (search.costs[next_idx], step);
+            }
+        }
+        field.directions[idx] = best.1.as_vec2().normalize_or_zero();
+    }
+}
+
+// a diagonal step may not squeeze between two blocked orthogonal cells
+fn cuts_corner(grid: &NavGrid, cell: IVec2, step: IVec2) -> bool {
+    step.x != 0
+        && step.y != 0
+        && (!grid.is_walkable(cell + IVec2::new(step.x, 0)) || !grid.is_walkable(cell + IVec2::new(0, step.y)))
+}
+
+fn index(origin: IVec2, cell: IVec2) -> Option<usize> {
+    let size = cfg::FLOW_FIELD_RADIUS * 2 + 1;
+    let local = cell - origin;
+    if local.x < 0 || local.y < 0 || local.x >= size || local.y >= size {
+        return None;
+    }
+    Some((local.y * size + local.x) as usize)
+}
+
+fn cell_at(origin: IVec2, idx: usize) -> IVec2 {
+    let size = (cfg::FLOW_FIELD_RADIUS * 2 + 1) as usize;
+    origin + IVec2::new((idx % size) as i32, (idx / size) as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid_with_walls(walls: &[IVec2]) -> NavGrid {
+        let mut grid = NavGrid::default();
+        grid.blocked.extend(walls.iter().copied());
+        grid
+    }
+
+    fn solve(grid: &NavGrid, goal: IVec2) -> (FlowFieldSearch, FlowField) {
+        let mut search = FlowFieldSearch::default();
+        start_search(&mut search, goal);
+        assert!(expand(&mut search, grid, usize::MAX));
+        let mut field = FlowField::default();
+        bake_directions(&search, grid, &mut field);
+        (search, field)
+    }
+
+    fn cost(search: &FlowFieldSearch, cell: IVec2) -> u32 {
+        search.costs[index(search.origin, cell).unwrap()]
+    }
+
+    fn direction(field: &FlowField, grid: &NavGrid, cell: IVec2) -> Option<Vec2> {
+        field.direction_at((cell.as_vec2() + 0.5) * grid.cell_size())
+    }
+
+    #[test]
+    fn open_ground_points_straight_at_the_goal() {
+        let grid = grid_with_walls(&[]);
+        let (search, field) = solve(&grid, IVec2::ZERO);
+
+        assert_eq!(cost(&search, IVec2::new(5, 0)), 50);
+        assert_eq!(cost(&search, IVec2::new(3, 3)), 42);
+        assert_eq!(direction(&field, &grid, IVec2::new(5, 0)), Some(Vec2::NEG_X));
+        assert_eq!(direction(&field, &grid, IVec2::new(3, 3)), Some(Vec2::new(-1.0, -1.0).normalize()));
+        assert_eq!(direction(&field, &grid, IVec2::ZERO), None);
+    }
+
+    #[test]
+    fn paths_go_around_a_wall() {
+        let wall: Vec<IVec2> = (-3..=3).map(|y| IVec2::new(2, y)).collect();
+        let grid = grid_with_walls(&wall);
+        let (search, field) = solve(&grid, IVec2::ZERO);
+
+        assert_eq!(cost(&search, IVec2::new(2, 0)), u32::MAX);
+        assert_eq!(direction(&field, &grid, IVec2::new(2, 0)), None);
+        // straight through would cost 30
+        assert!(cost(&search, IVec2::new(3, 0)) > 30);
+        let dir = direction(&field, &grid, IVec2::new(3, 0)).unwrap();
+        assert!(dir.x >= 0.0, "steps into the wall: {dir}");
+    }
+
+    #[test]
+    fn diagonals_do_not_cut_corners() {
+        let grid = grid_with_walls(&[IVec2::new(1, 0), IVec2::new(0, 1)]);
+        let (search, field) = solve(&grid, IVec2::ZERO);
+
+        assert!(cost(&search, IVec2::new(1, 1)) > 14);
+        assert_ne!(direction(&field, &grid, IVec2::new(1, 1)), Some(Vec2::new(-1.0, -1.0).normalize()));
+    }
+
+    #[test]
+    fn enclosed_cells_get_no_direction() {
+        let centre = IVec2::new(10, 10);
+        let ring: Vec<IVec2> = NEIGHBOURS.iter().map(|(step, _)| centre + *step).collect();
+        let grid = grid_with_walls(&ring);
+        let (search, field) = solve(&grid, IVec2::ZERO);
+
+        assert_eq!(cost(&search, centre), u32::MAX);
+        assert_eq!(direction(&field, &grid, centre), None);
+    }
+
+    #[test]
+    fn time_sliced_search_matches_a_single_pass() {
+        let wall: Vec<IVec2> = (-3..=3).map(|y| IVec2::new(2, y)).collect();
+        let grid = grid_with_walls(&wall);
+        let (full, _) = solve(&grid, IVec2::ZERO);
+
+        let mut sliced = FlowFieldSearch::default();
+        start_search(&mut sliced, IVec2::ZERO);
+        assert!(!expand(&mut sliced, &grid, 10));
+        while !expand(&mut sliced, &grid, 10) {}
+
+        assert_eq!(sliced.costs, full.costs);
+    }
+}
@@ -3,7 +3,9 @@ use bevy::window::PrimaryWindow;
 use bevy_ecs_tilemap::prelude::*;
 use noise::{NoiseFn, Perlin};
 use std::collections::{HashMap, VecDeque};
+use rand::Rng;
 use crate::game::config as cfg;
+use crate::game::rng::{GameRng, RngStream};
 
 const CHUNK_W: u32 = cfg::CHUNK_W;
 const CHUNK_H: u32 = cfg::CHUNK_H;
@@ -73,13 +75,10 @@ pub struct TerrainPlugin;
 
 impl Plugin for TerrainPlugin {
     fn build(&self, app: &mut App) {
-        // randomize seed at startup
-        let seed: u32 = rand::random::<u32>();
-
-        app.insert_resource(GenerationSeed(seed))
-            .insert_resource(CurrentChunks::default())
+        app.insert_resource(CurrentChunks::default())
             .insert_resource(ChunkCache::default())
             .add_plugins(TilemapPlugin)
+            .add_systems(Startup, seed_terrain)
             .add_systems(FixedUpdate, generate_chunks_around_player)
             .add_systems(FixedUpdate, cleanup_far_chunks)
             .add_systems(Update, update_ui);
@@ -88,6 +87,12 @@ impl Plugin for TerrainPlugin {
 
 
 
+// the map is derived from the run seed so a seeded run gets the same terrain
+fn seed_terrain(mut commands: Commands, mut rng: ResMut<GameRng>) {
+    let seed: u32 = rng.stream(RngStream::Terrain).random();
+    commands.insert_resource(GenerationSeed(seed));
+}
+
 fn world_to_chunk_coord(x: f32, y: f32) -> (i32, i32) {
     let cx = (x / (CHUNK_W as f32 * TILE_WORLD_W)).floor() as i32;
     let cy = (y / (CHUNK_H as f32 * TILE_WORLD_H)).floor() as i32;
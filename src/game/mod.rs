@@ -13,4 +13,9 @@ pub mod animation;
 pub mod collisions;
 pub mod spatial;
 pub mod assets;
+pub mod input;
+pub mod run;
+pub mod time_scale;
+pub mod rng;
+pub mod progression;
 
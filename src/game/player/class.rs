@@ -0,0 +1,156 @@
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use bevy_common_assets::ron::RonAssetPlugin;
+use serde::Deserialize;
+
+use crate::game::assets::GameAssets;
+use crate::game::common::components::characters::stats::Stats;
+use crate::game::config as cfg;
+use crate::game::game_state::GameState;
+use crate::game::resources::GlobalTextureAtlas;
+
+#[derive(Deserialize, Asset, TypePath)]
+pub struct ClassList {
+    pub classes: Vec<ClassSpec>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct ClassSpec {
+    pub id: String,
+    pub name: String,
+    pub sprite: ClassSprite,
+    pub stats: Stats,
+    // ids of entries in `weapons/weapons.ron`, equipped in order
+    pub starting_weapons: Vec<String>,
+    #[serde(default = "default_weapon_slots")]
+    pub weapon_slots: usize,
+}
+
+fn default_weapon_slots() -> usize {
+    cfg::DEFAULT_WEAPON_SLOTS
+}
+
+#[derive(Deserialize, Clone)]
+pub struct ClassSprite {
+    // own sprite sheet; `None` uses the global tileset atlas
+    pub sheet: Option<ClassSheet>,
+    pub idle: usize,
+    pub moving: Vec<usize>,
+    pub frame_time: Option<f32>,
+    pub scale: Option<f32>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct ClassSheet {
+    pub path: String,
+    pub tile_w: u32,
+    pub tile_h: u32,
+    pub columns: u32,
+    pub rows: u32,
+}
+
+// Which class the next run spawns as; pick one with `--class <id>`.
+#[derive(Resource)]
+pub struct SelectedClass(pub String);
+
+impl Default for SelectedClass {
+    fn default() -> Self {
+        SelectedClass(String::from(cfg::DEFAULT_CLASS))
+    }
+}
+
+// Atlas layouts of the classes that have their own sheet, built once when the
+// class list is loaded so every respawn reuses them.
+#[derive(Resource, Default)]
+pub struct ClassLayouts(HashMap<String, Handle<TextureAtlasLayout>>);
+
+impl ClassLayouts {
+    pub fn get(&self, class_id: &str) -> Option<Handle<TextureAtlasLayout>> {
+        self.0.get(class_id).cloned()
+    }
+}
+
+// Animation frames for the spawned player, copied from its class.
+#[derive(Component, Clone)]
+pub struct PlayerAnimation {
+    pub idle: usize,
+    pub moving: Vec<usize>,
+}
+
+pub struct PlayerClassPlugin;
+
+impl Plugin for PlayerClassPlugin {
+    fn build(&self, app: &mut App) {
+        let selected = class_from_args().map(SelectedClass).unwrap_or_default();
+        info!("Selected class: {}", selected.0);
+
+        app
+            .add_plugins(RonAssetPlugin::<ClassList>::new(&["classes.ron"]))
+            .insert_resource(selected)
+            .init_resource::<ClassLayouts>()
+            .add_systems(OnExit(GameState::Loading), build_class_layouts);
+    }
+}
+
+// `--class archer` or `--class=archer`; an unknown id falls back like any
+// other bad selection, see `ClassList::get_or_first`
+fn class_from_args() -> Option<String> {
+    let args: Vec<String> = std::env::args().collect();
+    args.iter().enumerate().find_map(|(i, arg)| match arg.strip_prefix("--class") {
+        Some("") => args.get(i + 1).cloned(),
+        Some(rest) => rest.strip_prefix('=').map(String::from),
+        None => None,
+    })
+}
+
+fn build_class_layouts(
+    game_assets: Res<GameAssets>,
+    class_lists: Res<Assets<ClassList>>,
+    mut class_layouts: ResMut<ClassLayouts>,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+) {
+    let Some(list) = class_lists.get(&game_assets.class_list) else { return };
+    for class in list.classes.iter() {
+        let Some(sheet) = &class.sprite.sheet else { continue };
+        let layout = TextureAtlasLayout::from_grid(
+            UVec2::new(sheet.tile_w, sheet.tile_h),
+            sheet.columns,
+            sheet.rows,
+            None,
+            None,
+        );
+        class_layouts.0.insert(class.id.clone(), layouts.add(layout));
+    }
+}
+
+impl ClassList {
+    // falls back to the first class so a typo in the selection doesn't leave
+    // the run without a player
+    pub fn get_or_first(&self, id: &str) -> Option<&ClassSpec> {
+        match self.classes.iter().find(|c| c.id == id) {
+            Some(class) => Some(class),
+            None => {
+                let fallback = self.classes.first()?;
+                warn!("Unknown class `{}`, falling back to `{}`", id, fallback.id);
+                Some(fallback)
+            }
+        }
+    }
+}
+
+impl ClassSprite {
+    // `layout` is the class's entry in `ClassLayouts`
+    pub fn to_sprite(
+        &self,
+        atlas: &GlobalTextureAtlas,
+        asset_server: &AssetServer,
+        layout: Option<Handle<TextureAtlasLayout>>,
+    ) -> Sprite {
+        let (image, layout) = match (&self.sheet, layout) {
+            (Some(sheet), Some(layout)) => (asset_server.load(sheet.path.clone()), layout),
+            _ => (atlas.image.clone(), atlas.layout.clone()),
+        };
+
+        Sprite::from_atlas_image(image, TextureAtlas { layout, index: self.idle })
+    }
+}
@@ -1,13 +1,13 @@
 use bevy::prelude::{Component, Sprite};
 
 use crate::game::common::components::characters::{
-    move_speed::MoveSpeed,
     health::Health,
     position::Position,
     char_state::State,
     stats::Stats,
 };
+use crate::game::progression::{experience::Experience, upgrades::AcquiredUpgrades};
 
 #[derive(Component, Debug)]
-#[require(Position, Health, MoveSpeed, State, Stats, Sprite)]
+#[require(Position, Health, State, Stats, Sprite, Experience, AcquiredUpgrades)]
 pub struct Player;
@@ -1,38 +1,38 @@
-use bevy::{ecs::{entity::Entity, query::With, system::Commands}, prelude::{ButtonInput, KeyCode, Query, Res, Time, Transform, Vec2}, sprite::Sprite, window::Window};
+use bevy::{ecs::{entity::Entity, query::{With, Without}, system::Commands}, prelude::{NextState, Query, Res, ResMut, State as CurrentState, Time, Transform, Vec2}, sprite::Sprite, window::Window};
 
-use crate::game::{common::components::characters::{move_speed::MoveSpeed, position::Position, char_state::State}, player::component::Player};
+use crate::game::{common::components::characters::{stats::EffectiveStats, position::Position, char_state::State}, player::component::Player};
+use crate::game::game_state::GameState;
+use crate::game::player::death::Dying;
+use crate::game::map::navigation::NavGrid;
+use crate::game::input::actions::{Action, ActionState};
 
 pub fn controls(
-    input: Res<ButtonInput<KeyCode>>,
+    actions: Res<ActionState>,
     time: Res<Time>,
-    player: Query<&MoveSpeed, With<Player>>,
-    mut query: Query<(&mut Position, &mut Sprite, &mut State), With<Player>>,
+    nav_grid: Res<NavGrid>,
+    player: Query<&EffectiveStats, With<Player>>,
+    mut query: Query<(&mut Position, &mut Sprite, &mut State), (With<Player>, Without<Dying>)>,
 ) {
     let speed: f32 = match player.single() {
-        Ok(ms) => ms.0 as f32,
+        Ok(stats) => stats.move_speed,
         Err(_) => 300.0,
     };
 
     for (mut pos, mut sprite, mut state) in &mut query {
-        let mut input_dir = Vec2::ZERO;
-        if input.pressed(KeyCode::ArrowRight) || input.pressed(KeyCode::KeyD) {
-            input_dir.x += 1.0;
-        }
-        if input.pressed(KeyCode::ArrowLeft) || input.pressed(KeyCode::KeyA) {
-            input_dir.x -= 1.0;
-        }
-        if input.pressed(KeyCode::ArrowUp) || input.pressed(KeyCode::KeyW) {
-            input_dir.y += 1.0;
-        }
-        if input.pressed(KeyCode::ArrowDown) || input.pressed(KeyCode::KeyS) {
-            input_dir.y -= 1.0;
-        }
+        // analog magnitude: a half-tilted stick moves at half speed
+        let dir = actions.move_axis();
 
-        if input_dir != Vec2::ZERO {
+        if dir != Vec2::ZERO {
             let dt = time.delta().as_secs_f32();
-            let dir = input_dir.normalize();
-            pos.x += dir.x * speed * dt;
-            pos.y += dir.y * speed * dt;
+            let step = dir * speed * dt;
+            // walls stop the player like they stop enemy paths; each axis is
+            // checked on its own so the player slides along them
+            if nav_grid.is_walkable(nav_grid.cell_of(Vec2::new(pos.x + step.x, pos.y))) {
+                pos.x += step.x;
+            }
+            if nav_grid.is_walkable(nav_grid.cell_of(Vec2::new(pos.x, pos.y + step.y))) {
+                pos.y += step.y;
+            }
             *state = State::Moving;
 
             // sprite flip based on horizontal input
@@ -58,15 +58,31 @@ pub fn sync_position_transform(mut query: Query<(&Position, &mut Transform)>) {
 pub fn close_on_esc(
     mut commands: Commands,
     focused_windows: Query<(Entity, &Window)>,
-    input: Res<ButtonInput<KeyCode>>,
+    actions: Res<ActionState>,
 ) {
     for (window, focus) in focused_windows.iter() {
         if !focus.focused {
             continue;
         }
 
-        if input.just_pressed(KeyCode::Escape) {
+        if actions.just_pressed(Action::Quit) {
             commands.entity(window).despawn();
         }
     }
 }
+
+pub fn toggle_pause(
+    actions: Res<ActionState>,
+    state: Res<CurrentState<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !actions.just_pressed(Action::Pause) {
+        return;
+    }
+
+    match state.get() {
+        GameState::InGame => next_state.set(GameState::Paused),
+        GameState::Paused => next_state.set(GameState::InGame),
+        _ => {}
+    }
+}
@@ -0,0 +1,49 @@
+use bevy::prelude::*;
+
+use crate::game::common::components::characters::char_state::State;
+use crate::game::config as cfg;
+use crate::game::game_state::GameState;
+use crate::game::player::component::Player;
+
+// Inserted on the player when it dies. The player stays in the world while the
+// death sequence plays, then the game moves to `GameState::GameOver`.
+#[derive(Component)]
+pub struct Dying(pub Timer);
+
+impl Dying {
+    pub fn new() -> Self {
+        Dying(Timer::from_seconds(cfg::PLAYER_DEATH_SEQUENCE_SECS, TimerMode::Once))
+    }
+}
+
+pub struct PlayerDeathPlugin;
+
+impl Plugin for PlayerDeathPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            play_death_sequence.run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+// spin and fade the player out, then end the run
+fn play_death_sequence(
+    time: Res<Time>,
+    mut query: Query<(&mut Dying, &mut Sprite, &mut Transform, &mut State), With<Player>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for (mut dying, mut sprite, mut transform, mut state) in query.iter_mut() {
+        dying.0.tick(time.delta());
+        *state = State::Idle;
+
+        let t = dying.0.fraction();
+        sprite.color = Color::srgba(1.0, 1.0 - t * 0.5, 1.0 - t * 0.5, 1.0 - t);
+        transform.rotation = Quat::from_rotation_z(t * std::f32::consts::PI * 2.0);
+
+        if dying.0.just_finished() {
+            info!("Death sequence finished, game over");
+            next_state.set(GameState::GameOver);
+        }
+    }
+}
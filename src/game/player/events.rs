@@ -2,26 +2,48 @@ use bevy::prelude::*;
 
 use crate::game::player::component::Player;
 use crate::game::common::components::characters::health::Health;
+use crate::game::common::components::characters::invulnerability::Invulnerable;
+use crate::game::config as cfg;
+use crate::game::time_scale::TimeScale;
 
 #[derive(Event)]
 pub struct PlayerDamagedEvent {
     pub damage: f32,
 }
 
+// fired once when the player's health reaches zero, before the death sequence
+#[derive(Event)]
+pub struct PlayerDiedEvent {
+    pub entity: Entity,
+}
+
 pub struct PlayerEventsPlugin;
 
 impl Plugin for PlayerEventsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_observer(|evt: On<PlayerDamagedEvent>, health_query: Query<&mut Health, With<Player>>| on_player_damaged(evt.event(), health_query));
+        app.add_observer(|evt: On<PlayerDamagedEvent>, commands: Commands, time_scale: ResMut<TimeScale>, health_query: Query<(Entity, &mut Health, Has<Invulnerable>), With<Player>>| on_player_damaged(evt.event(), commands, time_scale, health_query));
     }
 }
 
-fn on_player_damaged(event: &PlayerDamagedEvent, mut health_query: Query<&mut Health, With<Player>>) {
-    let mut health = match health_query.single_mut() {
+fn on_player_damaged(
+    event: &PlayerDamagedEvent,
+    mut commands: Commands,
+    mut time_scale: ResMut<TimeScale>,
+    mut health_query: Query<(Entity, &mut Health, Has<Invulnerable>), With<Player>>,
+) {
+    let (entity, mut health, invulnerable) = match health_query.single_mut() {
         Ok(h) => h,
         Err(_) => return,
     };
 
+    // hits during the invulnerability window are ignored entirely
+    if invulnerable || health.is_dead() {
+        return;
+    }
+
     health.take_damage(event.damage);
+    commands.entity(entity).insert(Invulnerable::from_seconds(cfg::PLAYER_INVULNERABILITY_SECS));
+    // brief freeze so the hit reads; runs on real time, see `TimeScale`
+    time_scale.hit_stop(cfg::PLAYER_HIT_STOP_SECS);
     // info!("Player took {} damage (health {}/{})", event.damage, health.current, health.max);
 }
@@ -0,0 +1,109 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::game::common::components::characters::health::Health;
+use crate::game::enemies::component::{Enemy, Knockback};
+use crate::game::player::component::Player;
+use crate::game::player::events::PlayerDamagedEvent;
+use crate::game::run::RunStats;
+use crate::game::spatial::KDTree2;
+
+// How damage drops off from the centre of an explosion to its edge.
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub enum Falloff {
+    #[default]
+    Linear,
+    Quadratic,
+}
+
+impl Falloff {
+    // damage multiplier at `distance` from the centre, 1.0 at the centre and 0.0 at the edge
+    pub fn factor(&self, distance: f32, radius: f32) -> f32 {
+        let t = (1.0 - distance / radius.max(0.001)).clamp(0.0, 1.0);
+        match self {
+            Falloff::Linear => t,
+            Falloff::Quadratic => t * t,
+        }
+    }
+}
+
+// Explosive projectile settings, part of a weapon's `ProjectileEffects`.
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub struct Explosion {
+    pub radius: f32,
+    #[serde(default)]
+    pub falloff: Falloff,
+    // push speed at the centre, scaled by falloff like the damage
+    #[serde(default)]
+    pub knockback: f32,
+    // fraction of the damage the player takes when caught in the blast
+    #[serde(default)]
+    pub self_damage: f32,
+    // rockets detonate on the first enemy they touch
+    #[serde(default = "default_true")]
+    pub on_impact: bool,
+    // grenades detonate when their lifetime (the fuse) runs out
+    #[serde(default)]
+    pub on_expire: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+// Fired whenever something detonates. Damage is applied by an observer in
+// `ExplosionPlugin`; particles, camera shake or sound can observe it too.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct ExplosionEvent {
+    pub position: Vec3,
+    // damage at the centre
+    pub damage: f32,
+    pub explosion: Explosion,
+}
+
+pub struct ExplosionPlugin;
+
+impl Plugin for ExplosionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(apply_explosion_damage);
+    }
+}
+
+fn apply_explosion_damage(
+    evt: On<ExplosionEvent>,
+    mut commands: Commands,
+    tree: Res<KDTree2>,
+    mut run_stats: ResMut<RunStats>,
+    mut enemy_query: Query<&mut Health, With<Enemy>>,
+    player_query: Query<&Transform, With<Player>>,
+) {
+    let event = evt.event();
+    let blast = event.explosion;
+    let center = event.position.truncate();
+
+    for (pos, entity) in tree.within_distance(event.position, blast.radius) {
+        let Some(enemy) = entity else { continue };
+        let Ok(mut health) = enemy_query.get_mut(enemy) else { continue };
+        if health.is_dead() {
+            continue;
+        }
+        let offset = pos.truncate() - center;
+        let factor = blast.falloff.factor(offset.length(), blast.radius);
+        run_stats.damage_dealt += health.take_damage(event.damage * factor);
+
+        if blast.knockback > 0.0 && !health.is_dead() {
+            let push = offset.normalize_or(Vec2::X) * blast.knockback * factor;
+            commands.entity(enemy).try_insert(Knockback(push));
+        }
+    }
+
+    if blast.self_damage > 0.0 {
+        if let Ok(player_transform) = player_query.single() {
+            let distance = player_transform.translation.truncate().distance(center);
+            if distance <= blast.radius {
+                let damage = event.damage * blast.self_damage * blast.falloff.factor(distance, blast.radius);
+                commands.trigger(PlayerDamagedEvent { damage });
+            }
+        }
+    }
+}
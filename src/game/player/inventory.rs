@@ -0,0 +1,150 @@
+use bevy::prelude::*;
+
+use crate::game::game_state::GameState;
+use crate::game::input::actions::{Action, ActionState};
+use crate::game::player::component::Player;
+use crate::game::player::death::Dying;
+use crate::game::player::weapon::{Weapon, WeaponModifiers};
+use crate::game::player::weapon_spec::{spawn_weapon, WeaponSpec};
+use crate::game::resources::GlobalTextureAtlas;
+
+#[derive(Clone, Copy, Debug)]
+pub struct InventorySlot {
+    pub weapon: Entity,
+    pub passive: bool,
+}
+
+// Weapons carried by the player, in slot order. One manual weapon is selected
+// at a time; passive weapons are always firing.
+#[derive(Component, Debug)]
+pub struct Inventory {
+    slots: Vec<InventorySlot>,
+    selected: Option<Entity>,
+    max_slots: usize,
+}
+
+impl Inventory {
+    pub fn new(max_slots: usize) -> Self {
+        Inventory {
+            slots: Vec::with_capacity(max_slots),
+            selected: None,
+            max_slots,
+        }
+    }
+
+    pub fn slots(&self) -> &[InventorySlot] {
+        &self.slots
+    }
+
+    pub fn selected(&self) -> Option<Entity> {
+        self.selected
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.slots.len() >= self.max_slots
+    }
+
+    // manual weapon in the given (0-based) slot, if any
+    fn manual_at(&self, index: usize) -> Option<Entity> {
+        self.slots.get(index).filter(|s| !s.passive).map(|s| s.weapon)
+    }
+
+    // manual weapon `step` positions away from the selected one, wrapping around
+    fn cycled(&self, step: i32) -> Option<Entity> {
+        let manual: Vec<Entity> = self.slots.iter().filter(|s| !s.passive).map(|s| s.weapon).collect();
+        if manual.is_empty() {
+            return None;
+        }
+        let current = self
+            .selected
+            .and_then(|e| manual.iter().position(|m| *m == e))
+            .unwrap_or(0) as i32;
+        let next = (current + step).rem_euclid(manual.len() as i32) as usize;
+        Some(manual[next])
+    }
+}
+
+// Spawns `spec` into the next free slot, with the weapon upgrades the player
+// has taken so far (`upgrades`, the player's own `WeaponModifiers`). Returns
+// `None` when the inventory is already full.
+pub fn equip_weapon(
+    commands: &mut Commands,
+    atlas: &GlobalTextureAtlas,
+    inventory: &mut Inventory,
+    upgrades: &WeaponModifiers,
+    spec: &WeaponSpec,
+) -> Option<Entity> {
+    if inventory.is_full() {
+        warn!("No free weapon slot for {}", spec.name);
+        return None;
+    }
+
+    let weapon = spawn_weapon(commands, atlas, spec, upgrades.0);
+    let passive = spec.mode.is_passive();
+    inventory.slots.push(InventorySlot { weapon, passive });
+
+    // the first manual weapon picked up gets selected, later ones are holstered
+    if !passive {
+        if inventory.selected.is_none() {
+            inventory.selected = Some(weapon);
+        } else {
+            commands.entity(weapon).insert(Visibility::Hidden);
+        }
+    }
+    info!("Equipped {} in slot {}", spec.name, inventory.slots.len());
+    Some(weapon)
+}
+
+pub struct InventoryPlugin;
+
+impl Plugin for InventoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (switch_weapons, sync_weapon_visibility)
+                .chain()
+                .run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+fn switch_weapons(
+    actions: Res<ActionState>,
+    mut player_query: Query<&mut Inventory, (With<Player>, Without<Dying>)>,
+) {
+    let Ok(mut inventory) = player_query.single_mut() else { return };
+
+    let mut choice = None;
+    if actions.just_pressed(Action::NextWeapon) {
+        choice = inventory.cycled(1);
+    }
+    if actions.just_pressed(Action::PrevWeapon) {
+        choice = inventory.cycled(-1);
+    }
+    for index in 0..inventory.slots.len() {
+        if actions.just_pressed(Action::WeaponSlot(index as u8 + 1)) {
+            choice = inventory.manual_at(index).or(choice);
+        }
+    }
+
+    // only write when the selection changes so visibility isn't resynced every frame
+    if choice.is_some() && choice != inventory.selected {
+        inventory.selected = choice;
+    }
+}
+
+fn sync_weapon_visibility(
+    player_query: Query<&Inventory, (With<Player>, Changed<Inventory>)>,
+    mut weapon_query: Query<&mut Visibility, With<Weapon>>,
+) {
+    for inventory in player_query.iter() {
+        for slot in inventory.slots.iter().filter(|s| !s.passive) {
+            let Ok(mut visibility) = weapon_query.get_mut(slot.weapon) else { continue };
+            *visibility = if inventory.selected == Some(slot.weapon) {
+                Visibility::Inherited
+            } else {
+                Visibility::Hidden
+            };
+        }
+    }
+}
@@ -2,4 +2,9 @@ pub mod controls;
 pub mod component;
 pub mod player;
 pub mod weapon;
+pub mod weapon_spec;
+pub mod inventory;
+pub mod explosion;
 pub mod events;
+pub mod death;
+pub mod class;
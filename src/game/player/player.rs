@@ -1,51 +1,78 @@
 use bevy::prelude::*;
-use bevy::time::Stopwatch;
-use crate::game::player::weapon::{Weapon, WeaponTimer};
 
 use crate::game::player::weapon::GunPlugin;
+use crate::game::player::death::PlayerDeathPlugin;
+use crate::game::player::explosion::ExplosionPlugin;
 use crate::game::resources::GlobalTextureAtlas;
+use crate::game::game_state::GameState;
+use crate::game::assets::GameAssets;
+use crate::game::player::class::{ClassLayouts, ClassList, PlayerAnimation, PlayerClassPlugin, SelectedClass};
+use crate::game::player::weapon_spec::WeaponList;
+use crate::game::player::inventory::{equip_weapon, Inventory, InventoryPlugin};
+use crate::game::player::weapon::WeaponModifiers;
 use crate::game::animation::animation::{PlayerAnimationPlugin, AnimationTimer, AtlasIndex};
 use crate::game::player::{
         component::Player,
         events::PlayerEventsPlugin,
-        controls::{controls, sync_position_transform, close_on_esc},
+        controls::{controls, sync_position_transform, close_on_esc, toggle_pause},
     };
 
 pub struct PlayerPlugin;
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         app
-        .add_plugins((PlayerEventsPlugin, PlayerAnimationPlugin, GunPlugin))
-        .add_systems(Startup, setup.after(crate::game::resources::load_assets))
+        .add_plugins((PlayerEventsPlugin, PlayerAnimationPlugin, GunPlugin, PlayerDeathPlugin, PlayerClassPlugin, InventoryPlugin, ExplosionPlugin))
+        // spawn once assets are loaded, and again on restart (the previous
+        // run's entities are despawned on leaving GameOver)
+        .add_systems(OnTransition { exited: GameState::Loading, entered: GameState::InGame }, setup)
+        .add_systems(OnTransition { exited: GameState::GameOver, entered: GameState::InGame }, setup)
         .add_systems(Update,
-            (controls, close_on_esc))
+            (controls.run_if(in_state(GameState::InGame)), close_on_esc, toggle_pause))
         .add_systems(FixedUpdate, sync_position_transform);
     }
 }
 
-fn setup(mut commands: Commands, handle: Res<GlobalTextureAtlas>) {
-    commands.spawn(Player)
-        .insert(Sprite::from_atlas_image(
-            handle.image.clone(),
-            TextureAtlas {
-                layout:handle.layout.clone(),
-                index:2
+fn setup(
+    mut commands: Commands,
+    handle: Res<GlobalTextureAtlas>,
+    game_assets: Res<GameAssets>,
+    class_lists: Res<Assets<ClassList>>,
+    weapon_lists: Res<Assets<WeaponList>>,
+    selected: Res<SelectedClass>,
+    asset_server: Res<AssetServer>,
+    class_layouts: Res<ClassLayouts>,
+) {
+    let Some(class) = class_lists
+        .get(&game_assets.class_list)
+        .and_then(|list| list.get_or_first(&selected.0))
+    else {
+        error!("No player classes available in classes/classes.ron");
+        return;
+    };
+    info!("Spawning player as {}", class.name);
+
+    // weapons are separate entities so systems can query them; the player
+    // only keeps track of them through its inventory
+    let mut inventory = Inventory::new(class.weapon_slots);
+    // weapon upgrades taken during the run, copied onto weapons equipped later
+    let upgrades = WeaponModifiers::default();
+    let weapon_list = weapon_lists.get(&game_assets.weapon_list);
+    for id in &class.starting_weapons {
+        match weapon_list.and_then(|list| list.get(id)) {
+            Some(spec) => {
+                equip_weapon(&mut commands, &handle, &mut inventory, &upgrades, spec);
             }
-        ))
-        .insert(AtlasIndex(2))
-        .insert(AnimationTimer(Timer::from_seconds(0.15, TimerMode::Repeating)));
+            None => error!("Class `{}` starts with unknown weapon `{}`", class.id, id),
+        }
+    }
 
-    // spawn the player's gun as a separate entity so systems can query it
-    commands.spawn((
-        Sprite::from_atlas_image(
-            handle.image.clone(),
-            TextureAtlas {
-                layout: handle.layout.clone(),
-                index: 17,
-            },
-        ),
-        Weapon,
-        WeaponTimer(Stopwatch::new()),
-        Transform::from_xyz(0.0, 0.0, 15.0),
-    ));
+    let sprite = &class.sprite;
+    commands.spawn((Player, inventory, upgrades, DespawnOnExit(GameState::GameOver)))
+        .insert(sprite.to_sprite(&handle, &asset_server, class_layouts.get(&class.id)))
+        .insert(Transform::from_scale(Vec3::splat(sprite.scale.unwrap_or(1.0))))
+        .insert(AtlasIndex(0))
+        .insert(PlayerAnimation { idle: sprite.idle, moving: sprite.moving.clone() })
+        .insert(AnimationTimer(Timer::from_seconds(sprite.frame_time.unwrap_or(0.15), TimerMode::Repeating)))
+        // health and speed are derived from these by the stat pipeline
+        .insert(class.stats.clone());
 }
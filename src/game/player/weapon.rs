@@ -1,116 +1,358 @@
-use std::f32::consts::PI;
-use bevy::platform::time::Instant;
+use std::f32::consts::{PI, TAU};
 
-use bevy::math::{vec2, vec3};
+use bevy::math::vec3;
 use bevy::prelude::*;
 use bevy::time::Stopwatch;
 use rand::Rng;
 
+use crate::game::rng::{GameRng, RngStream};
+use serde::Deserialize;
+
 use crate::game::player::component::Player;
+use crate::game::common::components::characters::stats::EffectiveStats;
 use crate::game::game_state::GameState;
-use crate::game::config::{BULLET_SPEED, BULLET_SPAWN_INTERVAL, BULLET_TIME_SECS, NUM_BULLETS_PER_SHOT, SPRITE_SCALE, BULLET_SPREAD};
+use crate::game::config::{AUTO_AIM_RANGE, SPRITE_SCALE, WEAPON_HOLD_OFFSET};
+use crate::game::common::components::characters::health::Health;
+use crate::game::enemies::component::Enemy;
+use crate::game::spatial::KDTree2;
+use crate::game::player::explosion::{Explosion, ExplosionEvent};
 use crate::game::resources::{CursorPosition, GlobalTextureAtlas};
+use crate::game::input::actions::{Action, ActionState};
+use crate::game::player::inventory::Inventory;
+use crate::game::player::weapon_spec::{AttackKind, MeleeShape, MeleeSpec, WeaponList, WeaponMode, WeaponSpec};
+use bevy_common_assets::ron::RonAssetPlugin;
 
 pub struct GunPlugin;
 
 #[derive(Component)]
+#[require(WeaponModifiers)]
 pub struct Weapon;
+
+// Adjustments on top of a weapon's base behaviour, e.g. from upgrades.
+// Several modifiers stack by summing their fields.
+#[derive(Deserialize, Clone, Copy, Debug, Default)]
+pub struct WeaponModifier {
+    #[serde(default)]
+    pub extra_projectiles: i32,
+    #[serde(default)]
+    pub spread_percent: f32,
+    #[serde(default)]
+    pub projectile_speed_percent: f32,
+    #[serde(default)]
+    pub extra_pierce: i32,
+    #[serde(default)]
+    pub extra_bounces: i32,
+    #[serde(default)]
+    pub extra_knockback: f32,
+    #[serde(default)]
+    pub extra_chain_jumps: i32,
+    #[serde(default)]
+    pub extra_turn_rate: f32,
+    #[serde(default)]
+    pub explosion_radius_percent: f32,
+}
+
+#[derive(Component, Default)]
+pub struct WeaponModifiers(pub WeaponModifier);
+
+impl WeaponModifiers {
+    pub fn stack(&mut self, other: &WeaponModifier) {
+        self.0.extra_projectiles += other.extra_projectiles;
+        self.0.spread_percent += other.spread_percent;
+        self.0.projectile_speed_percent += other.projectile_speed_percent;
+        self.0.extra_pierce += other.extra_pierce;
+        self.0.extra_bounces += other.extra_bounces;
+        self.0.extra_knockback += other.extra_knockback;
+        self.0.extra_chain_jumps += other.extra_chain_jumps;
+        self.0.extra_turn_rate += other.extra_turn_rate;
+        self.0.explosion_radius_percent += other.explosion_radius_percent;
+    }
+}
+
+// What a projectile does when it hits. Authored per weapon, copied onto each
+// bullet with the weapon's modifiers applied; `pierce` and `bounces` are
+// counted down as the bullet uses them up.
+#[derive(Component, Deserialize, Clone, Copy, Debug)]
+#[serde(default)]
+pub struct ProjectileEffects {
+    // enemies the bullet passes through before it stops
+    pub pierce: u32,
+    // redirects to the nearest enemy not hit yet, once pierce is used up
+    pub bounces: u32,
+    pub bounce_range: f32,
+    // initial push speed applied to the enemy hit
+    pub knockback: f32,
+    // lightning arcs from the enemy hit to this many others nearby
+    pub chain_jumps: u32,
+    pub chain_range: f32,
+    // damage kept on each jump (0.5 = half of the previous hit)
+    pub chain_damage: f32,
+    // homing: radians per second the bullet may turn towards its target (0 = off)
+    pub turn_rate: f32,
+    // how far away a homing bullet can lock on to an enemy
+    pub homing_radius: f32,
+    // makes the bullet explode instead of hitting a single enemy
+    pub explosion: Option<Explosion>,
+}
+
+impl Default for ProjectileEffects {
+    fn default() -> Self {
+        ProjectileEffects {
+            pierce: 0,
+            bounces: 0,
+            bounce_range: 150.0,
+            knockback: 0.0,
+            chain_jumps: 0,
+            chain_range: 80.0,
+            chain_damage: 0.5,
+            turn_rate: 0.0,
+            homing_radius: 200.0,
+            explosion: None,
+        }
+    }
+}
+
+impl ProjectileEffects {
+    pub fn with_modifier(&self, m: &WeaponModifier) -> Self {
+        ProjectileEffects {
+            pierce: (self.pierce as i32 + m.extra_pierce).max(0) as u32,
+            bounces: (self.bounces as i32 + m.extra_bounces).max(0) as u32,
+            knockback: (self.knockback + m.extra_knockback).max(0.0),
+            chain_jumps: (self.chain_jumps as i32 + m.extra_chain_jumps).max(0) as u32,
+            turn_rate: (self.turn_rate + m.extra_turn_rate).max(0.0),
+            explosion: self.explosion.map(|e| Explosion {
+                radius: e.radius * (1.0 + m.explosion_radius_percent).max(0.1),
+                ..e
+            }),
+            ..*self
+        }
+    }
+}
+
+// enemy a homing bullet is steering towards; reacquired when it dies
+#[derive(Component, Default)]
+pub struct HomingTarget(pub Option<Entity>);
+
+// Survivor-style play: manual weapons aim at the nearest enemy and fire on
+// their own. Toggled with `Action::ToggleAutoAim`.
+#[derive(Resource, Default)]
+pub struct AutoAim {
+    pub enabled: bool,
+}
+
+// enemies this bullet already damaged; they are skipped on later contacts
+#[derive(Component, Default)]
+pub struct HitEnemies(pub Vec<Entity>);
 #[derive(Component)]
 pub struct WeaponTimer(pub Stopwatch);
 #[derive(Component)]
 pub struct Bullet;
+// damage dealt on hit, snapshotted from the shooter's stats when fired
+#[derive(Component)]
+pub struct BulletDamage(pub f32);
+// ticks on virtual time, so bullets don't age while the game is paused
 #[derive(Component)]
-pub struct SpawnInstant(Instant);
+pub struct BulletLifetime(pub Timer);
 #[derive(Component)]
-struct BulletDirection(Vec3);
+pub struct BulletDirection(pub Vec3);
+
+// A melee attack in progress. Its hits are resolved once, on the frame it is
+// spawned (see `resolve_melee_swings`); afterwards it only plays the sweep.
+#[derive(Component)]
+pub struct MeleeSwing {
+    pub spec: MeleeSpec,
+    pub origin: Vec2,
+    pub direction: Vec2,
+    pub damage: f32,
+    pub knockback: f32,
+    pub timer: Timer,
+}
+#[derive(Component)]
+struct BulletSpeed(f32);
 
 impl Plugin for GunPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            (
-                update_gun_transform,
-                update_bullets,
-                handle_gun_input,
-                despawn_old_bullets,
-            )
-                .run_if(in_state(GameState::InGame)),
-        );
+        app.add_plugins(RonAssetPlugin::<WeaponList>::new(&["weapons.ron"]))
+            .init_resource::<AutoAim>()
+            .add_systems(
+                Update,
+                (
+                    toggle_auto_aim,
+                    update_gun_transform,
+                    steer_homing_bullets.before(update_bullets),
+                    update_bullets,
+                    handle_gun_input,
+                    despawn_old_bullets,
+                    update_melee_swings,
+                )
+                    .run_if(in_state(GameState::InGame)),
+            );
     }
 }
 
+// expired bullets just disappear, except fused explosives which go off
 fn despawn_old_bullets(
     mut commands: Commands,
-    bullet_query: Query<(&SpawnInstant, Entity), With<Bullet>>,
+    time: Res<Time>,
+    mut bullet_query: Query<(&mut BulletLifetime, &Transform, &BulletDamage, &ProjectileEffects, Entity), With<Bullet>>,
 ) {
-    for (instant, e) in bullet_query.iter() {
-        if instant.0.elapsed().as_secs_f32() > BULLET_TIME_SECS {
-            commands.entity(e).despawn();
+    for (mut lifetime, transform, damage, effects, e) in bullet_query.iter_mut() {
+        if !lifetime.0.tick(time.delta()).is_finished() {
+            continue;
         }
+        if let Some(explosion) = effects.explosion.filter(|x| x.on_expire) {
+            commands.trigger(ExplosionEvent { position: transform.translation, damage: damage.0, explosion });
+        }
+        commands.entity(e).despawn();
+    }
+}
+
+fn toggle_auto_aim(actions: Res<ActionState>, mut auto_aim: ResMut<AutoAim>) {
+    if actions.just_pressed(Action::ToggleAutoAim) {
+        auto_aim.enabled = !auto_aim.enabled;
+        info!("Auto aim {}", if auto_aim.enabled { "on" } else { "off" });
     }
 }
 
 fn update_gun_transform(
+    time: Res<Time>,
     cursor_pos: Res<CursorPosition>,
-    player_query: Query<&Transform, With<Player>>,
-    mut gun_query: Query<&mut Transform, (With<Weapon>, Without<Player>)>,
+    actions: Res<ActionState>,
+    auto_aim: Res<AutoAim>,
+    tree: Res<KDTree2>,
+    player_query: Query<(&Transform, &Inventory), With<Player>>,
+    mut gun_query: Query<(&mut Transform, &WeaponSpec), (With<Weapon>, Without<Player>)>,
 ) {
-    if player_query.is_empty() || gun_query.is_empty() {
-        return;
-    }
-    let player_pos = match player_query.single() {
-        Ok(t) => t.translation.truncate(),
-        Err(_) => return,
-    };
-    let cursor_pos = match cursor_pos.0 {
-        Some(pos) => pos,
-        None => player_pos,
-    };
-    let mut gun_transform = match gun_query.single_mut() {
-        Ok(g) => g,
-        Err(_) => return,
+    let Ok((player_transform, inventory)) = player_query.single() else { return };
+    let player_pos = player_transform.translation.truncate();
+    let cursor_pos = cursor_pos.0.unwrap_or(player_pos);
+
+    // with auto aim on, the nearest enemy in range wins; otherwise right stick
+    // aim takes priority and the mouse cursor is the fallback
+    let nearest_enemy = auto_aim
+        .enabled
+        .then(|| tree.nearest_neighbour(player_transform.translation))
+        .flatten()
+        .map(|(pos, _)| pos.truncate())
+        .filter(|pos| pos.distance(player_pos) <= AUTO_AIM_RANGE);
+    let aim_angle = match (nearest_enemy, actions.aim_direction()) {
+        (Some(target), _) => (target - player_pos).to_angle(),
+        (None, Some(dir)) => dir.y.atan2(dir.x),
+        (None, None) => (player_pos.y - cursor_pos.y).atan2(player_pos.x - cursor_pos.x) + PI,
     };
 
-    let angle = (player_pos.y - cursor_pos.y).atan2(player_pos.x - cursor_pos.x) + PI;
-    gun_transform.rotation = Quat::from_rotation_z(angle);
+    let num_orbiting = inventory
+        .slots()
+        .iter()
+        .filter(|slot| {
+            gun_query
+                .get(slot.weapon)
+                .is_ok_and(|(_, spec)| matches!(spec.mode, WeaponMode::Orbit { .. }))
+        })
+        .count();
+    let mut orbit_index = 0;
+    let mut trail_index = 0;
+
+    for slot in inventory.slots() {
+        let Ok((mut gun_transform, spec)) = gun_query.get_mut(slot.weapon) else { continue };
 
-    let offset = 20.0;
-    let new_gun_pos = vec2(
-        player_pos.x + offset * angle.cos(),
-        player_pos.y + offset * angle.sin(),
-    );
+        let (pos, angle) = match spec.mode {
+            WeaponMode::Manual => (
+                player_pos + Vec2::from_angle(aim_angle) * WEAPON_HOLD_OFFSET,
+                aim_angle,
+            ),
+            WeaponMode::Orbit { radius, speed } => {
+                // orbiting weapons are spaced evenly around the player
+                let phase = orbit_index as f32 * TAU / num_orbiting as f32;
+                orbit_index += 1;
+                let angle = time.elapsed_secs() * speed + phase;
+                (player_pos + Vec2::from_angle(angle) * radius, angle)
+            }
+            WeaponMode::Trail { distance } => {
+                // keep a fixed distance from the player in whatever direction
+                // the weapon already is, so it drags behind while moving;
+                // several trailing weapons form a chain
+                trail_index += 1;
+                let offset = gun_transform.translation.truncate() - player_pos;
+                let dir = offset.normalize_or(-Vec2::from_angle(aim_angle));
+                (player_pos + dir * distance * trail_index as f32, aim_angle)
+            }
+        };
 
-    gun_transform.translation = vec3(new_gun_pos.x, new_gun_pos.y, gun_transform.translation.z);
-    gun_transform.translation.z = 15.0;
+        gun_transform.rotation = Quat::from_rotation_z(angle);
+        gun_transform.translation = vec3(pos.x, pos.y, 15.0);
+    }
 }
 
 fn handle_gun_input(
     mut commands: Commands,
     time: Res<Time>,
-    mut gun_query: Query<(&Transform, &mut WeaponTimer), With<Weapon>>,
-    mouse_button_input: Res<ButtonInput<MouseButton>>,
+    mut gun_query: Query<(Entity, &Transform, &mut WeaponTimer, &WeaponSpec, &WeaponModifiers), With<Weapon>>,
+    actions: Res<ActionState>,
     handle: Res<GlobalTextureAtlas>,
+    auto_aim: Res<AutoAim>,
+    mut game_rng: ResMut<GameRng>,
+    player_query: Query<(&Transform, &EffectiveStats, &Inventory), With<Player>>,
 ) {
-    if gun_query.is_empty() || !mouse_button_input.pressed(MouseButton::Left) {
-        return;
-    }
+    let Ok((player_transform, stats, inventory)) = player_query.single() else { return };
+    let rng = game_rng.stream(RngStream::Weapons);
 
-    let (gun_transform, mut gun_timer) = match gun_query.single_mut() {
-        Ok(t) => t,
-        Err(_) => return,
-    };
-    let gun_pos = gun_transform.translation.truncate();
-    gun_timer.0.tick(time.delta());
+    for (entity, gun_transform, mut gun_timer, spec, modifiers) in gun_query.iter_mut() {
+        gun_timer.0.tick(time.delta());
 
-    let mut rng = rand::rng();
-    let bullet_direction = gun_transform.local_x();
-    if gun_timer.0.elapsed_secs() >= BULLET_SPAWN_INTERVAL {
+        // passive weapons fire on their own, manual ones only while selected
+        let firing = spec.mode.is_passive()
+            || (inventory.selected() == Some(entity) && (auto_aim.enabled || actions.pressed(Action::Fire)));
+        // attack_speed is a rate multiplier: 2.0 fires twice as often
+        let interval = spec.fire_interval / stats.attack_speed.max(0.01);
+        if !firing || gun_timer.0.elapsed_secs() < interval {
+            continue;
+        }
         gun_timer.0.reset();
 
-        for _ in 0..NUM_BULLETS_PER_SHOT {
+        let gun_pos = gun_transform.translation.truncate();
+        let bullet_direction = gun_transform.local_x();
+        let m = &modifiers.0;
+
+        if let AttackKind::Melee(melee) = spec.attack {
+            // held weapons swing from the player, passive ones from where they are
+            let origin = if spec.mode.is_passive() {
+                gun_pos
+            } else {
+                player_transform.translation.truncate()
+            };
+            commands.spawn((
+                Sprite::from_atlas_image(
+                    handle.image.clone(),
+                    TextureAtlas {
+                        layout: handle.layout.clone(),
+                        index: spec.projectile_sprite,
+                    },
+                ),
+                Transform::from_translation(origin.extend(12.0)),
+                MeleeSwing {
+                    spec: melee,
+                    origin,
+                    direction: bullet_direction.truncate(),
+                    damage: stats.damage * spec.damage_multiplier,
+                    knockback: melee.knockback + m.extra_knockback,
+                    timer: Timer::from_seconds(melee.swing_time, TimerMode::Once),
+                },
+                DespawnOnExit(GameState::GameOver),
+            ));
+            continue;
+        }
+
+        let num_bullets = (spec.projectiles_per_shot as i32 + m.extra_projectiles).max(1);
+        let spread = (spec.spread * (1.0 + m.spread_percent)).max(0.001);
+        let speed = spec.projectile_speed * (1.0 + m.projectile_speed_percent).max(0.1);
+        let damage = stats.damage * spec.damage_multiplier;
+        let effects = spec.projectile.with_modifier(m);
+
+        for _ in 0..num_bullets {
             let dir = vec3(
-                bullet_direction.x + rng.random_range(-BULLET_SPREAD..BULLET_SPREAD),
-                bullet_direction.y + rng.random_range(-BULLET_SPREAD..BULLET_SPREAD),
+                bullet_direction.x + rng.random_range(-spread..spread),
+                bullet_direction.y + rng.random_range(-spread..spread),
                 bullet_direction.z,
             );
             commands.spawn((
@@ -118,21 +360,98 @@ fn handle_gun_input(
                     handle.image.clone(),
                     TextureAtlas {
                         layout: handle.layout.clone(),
-                        index: 16,
+                        index: spec.projectile_sprite,
                     },
                 ),
                 Transform::from_translation(vec3(gun_pos.x, gun_pos.y, 1.0))
                     .with_scale(Vec3::splat(SPRITE_SCALE as f32)),
                 Bullet,
                 BulletDirection(dir),
-                SpawnInstant(Instant::now()),
+                BulletSpeed(speed),
+                BulletDamage(damage),
+                effects,
+                HitEnemies::default(),
+                HomingTarget::default(),
+                BulletLifetime(Timer::from_seconds(spec.projectile_lifetime, TimerMode::Once)),
+                DespawnOnExit(GameState::GameOver),
             ));
         }
     }
 }
 
+// sweeps the swing sprite across the attacked area, then removes it
+fn update_melee_swings(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut swing_query: Query<(Entity, &mut Transform, &mut MeleeSwing)>,
+) {
+    for (entity, mut transform, mut swing) in swing_query.iter_mut() {
+        if swing.timer.tick(time.delta()).is_finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let t = swing.timer.fraction();
+        let base = swing.direction.to_angle();
+        let reach = swing.spec.reach;
+        let (pos, angle) = match swing.spec.shape {
+            MeleeShape::Arc => {
+                let angle = base + swing.spec.arc_degrees.to_radians() * (t - 0.5);
+                (swing.origin + Vec2::from_angle(angle) * reach * 0.7, angle)
+            }
+            MeleeShape::Spin => {
+                let angle = base + TAU * t;
+                (swing.origin + Vec2::from_angle(angle) * reach * 0.7, angle)
+            }
+            MeleeShape::Whip => (swing.origin + swing.direction * reach * t, base),
+        };
+        transform.translation = pos.extend(transform.translation.z);
+        transform.rotation = Quat::from_rotation_z(angle);
+    }
+}
+
+// Rotates homing bullets towards their target by at most `turn_rate` per
+// second. Targets are picked from the KD tree and dropped once they die,
+// despawn or are hit; enemies the bullet already hit are never picked again,
+// so piercing and bouncing bullets move on instead of circling them.
+fn steer_homing_bullets(
+    time: Res<Time>,
+    tree: Res<KDTree2>,
+    mut bullet_query: Query<
+        (&Transform, &mut BulletDirection, &mut HomingTarget, &ProjectileEffects, &HitEnemies),
+        With<Bullet>,
+    >,
+    enemy_query: Query<(&Transform, &Health), With<Enemy>>,
+) {
+    let dt = time.delta_secs();
+    for (transform, mut direction, mut target, effects, hits) in bullet_query.iter_mut() {
+        if effects.turn_rate <= 0.0 {
+            continue;
+        }
+        let bullet_pos = transform.translation.truncate();
+
+        let alive = |e: Entity| enemy_query.get(e).is_ok_and(|(_, h)| !h.is_dead());
+        if !target.0.is_some_and(|t| alive(t) && !hits.0.contains(&t)) {
+            target.0 = tree
+                .within_distance(transform.translation, effects.homing_radius)
+                .into_iter()
+                .filter_map(|(pos, e)| e.map(|e| (pos.truncate(), e)))
+                .filter(|(_, e)| !hits.0.contains(e) && alive(*e))
+                .min_by(|(a, _), (b, _)| a.distance_squared(bullet_pos).total_cmp(&b.distance_squared(bullet_pos)))
+                .map(|(_, e)| e);
+        }
+
+        let Some((enemy_transform, _)) = target.0.and_then(|e| enemy_query.get(e).ok()) else { continue };
+        let current = direction.0.truncate();
+        let wanted = enemy_transform.translation.truncate() - bullet_pos;
+        let max_turn = effects.turn_rate * dt;
+        let turn = current.angle_to(wanted).clamp(-max_turn, max_turn);
+        direction.0 = Vec2::from_angle(turn).rotate(current).extend(direction.0.z);
+    }
+}
+
 fn update_bullets(
-    mut bullet_query: Query<(&mut Transform, &BulletDirection), With<Bullet>>,
+    mut bullet_query: Query<(&mut Transform, &BulletDirection, &BulletSpeed), With<Bullet>>,
     time: Res<Time>,
 ) {
     if bullet_query.is_empty() {
@@ -140,8 +459,8 @@ fn update_bullets(
     }
 
     let delta = time.delta().as_secs_f32();
-    for (mut t, dir) in bullet_query.iter_mut() {
-        t.translation += dir.0.normalize() * BULLET_SPEED * delta;
+    for (mut t, dir, speed) in bullet_query.iter_mut() {
+        t.translation += dir.0.normalize() * speed.0 * delta;
         t.translation.z = 10.0;
     }
 }
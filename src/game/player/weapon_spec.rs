@@ -0,0 +1,141 @@
+use bevy::prelude::*;
+use bevy::time::Stopwatch;
+use serde::Deserialize;
+
+use crate::game::game_state::GameState;
+use crate::game::player::weapon::{ProjectileEffects, Weapon, WeaponModifier, WeaponModifiers, WeaponTimer};
+use crate::game::resources::GlobalTextureAtlas;
+
+// Weapon definitions, loaded from `assets/weapons/weapons.ron`.
+#[derive(Deserialize, Asset, TypePath)]
+pub struct WeaponList {
+    pub weapons: Vec<WeaponSpec>,
+}
+
+// Per-weapon parameters. A copy lives on every `Weapon` entity and the gun
+// systems read it from there, so two weapons never share behaviour by accident.
+#[derive(Deserialize, Component, Clone, Debug)]
+pub struct WeaponSpec {
+    pub id: String,
+    pub name: String,
+    // atlas indices in the global tileset
+    pub sprite: usize,
+    pub projectile_sprite: usize,
+    // seconds between shots (or swings) at attack_speed 1.0
+    pub fire_interval: f32,
+    // projectile settings, unused by melee weapons
+    #[serde(default = "default_projectiles_per_shot")]
+    pub projectiles_per_shot: usize,
+    #[serde(default)]
+    pub spread: f32,
+    #[serde(default)]
+    pub projectile_speed: f32,
+    #[serde(default)]
+    pub projectile_lifetime: f32,
+    // scales the wielder's effective damage stat
+    pub damage_multiplier: f32,
+    #[serde(default)]
+    pub mode: WeaponMode,
+    #[serde(default)]
+    pub attack: AttackKind,
+    #[serde(default)]
+    pub projectile: ProjectileEffects,
+}
+
+fn default_projectiles_per_shot() -> usize {
+    1
+}
+
+// How a weapon deals damage: spawning bullets, or an instant melee swing.
+#[derive(Deserialize, Clone, Copy, Debug, Default)]
+pub enum AttackKind {
+    #[default]
+    Projectile,
+    Melee(MeleeSpec),
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum MeleeShape {
+    // cone of `arc_degrees` in front of the wielder
+    Arc,
+    // full circle around the wielder
+    Spin,
+    // straight line `width` wide
+    Whip,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub struct MeleeSpec {
+    pub shape: MeleeShape,
+    pub reach: f32,
+    #[serde(default = "default_arc_degrees")]
+    pub arc_degrees: f32,
+    #[serde(default = "default_whip_width")]
+    pub width: f32,
+    #[serde(default)]
+    pub knockback: f32,
+    // how long the swing is shown; hits land on the first frame
+    #[serde(default = "default_swing_time")]
+    pub swing_time: f32,
+}
+
+fn default_arc_degrees() -> f32 {
+    90.0
+}
+
+fn default_whip_width() -> f32 {
+    16.0
+}
+
+fn default_swing_time() -> f32 {
+    0.15
+}
+
+// How a weapon is carried. Manual weapons are held in front of the player and
+// fire on `Action::Fire` while selected; the others fire on their own.
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub enum WeaponMode {
+    #[default]
+    Manual,
+    // circles the player at `radius`, `speed` in radians per second, firing outwards
+    Orbit { radius: f32, speed: f32 },
+    // follows `distance` behind the player, firing towards the aim direction
+    Trail { distance: f32 },
+}
+
+impl WeaponMode {
+    pub fn is_passive(&self) -> bool {
+        *self != WeaponMode::Manual
+    }
+}
+
+impl WeaponList {
+    pub fn get(&self, id: &str) -> Option<&WeaponSpec> {
+        self.weapons.iter().find(|w| w.id == id)
+    }
+}
+
+pub fn spawn_weapon(
+    commands: &mut Commands,
+    atlas: &GlobalTextureAtlas,
+    spec: &WeaponSpec,
+    modifiers: WeaponModifier,
+) -> Entity {
+    commands
+        .spawn((
+            Sprite::from_atlas_image(
+                atlas.image.clone(),
+                TextureAtlas {
+                    layout: atlas.layout.clone(),
+                    index: spec.sprite,
+                },
+            ),
+            Weapon,
+            WeaponTimer(Stopwatch::new()),
+            WeaponModifiers(modifiers),
+            spec.clone(),
+            Transform::from_xyz(0.0, 0.0, 15.0),
+            DespawnOnExit(GameState::GameOver),
+        ))
+        .id()
+}
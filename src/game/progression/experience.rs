@@ -0,0 +1,156 @@
+use bevy::prelude::*;
+
+use crate::game::common::components::characters::stats::EffectiveStats;
+use crate::game::config as cfg;
+use crate::game::enemies::events::EnemyKilledEvent;
+use crate::game::game_state::GameState;
+use crate::game::player::component::Player;
+use crate::game::player::death::Dying;
+use crate::game::resources::GlobalTextureAtlas;
+
+#[derive(Component, Debug)]
+pub struct Experience {
+    pub level: u32,
+    pub current: f32,
+    pub to_next: f32,
+}
+
+impl Default for Experience {
+    fn default() -> Self {
+        Experience {
+            level: 1,
+            current: 0.0,
+            to_next: Experience::required_for(1),
+        }
+    }
+}
+
+impl Experience {
+    // xp needed to go from `level` to `level + 1`
+    pub fn required_for(level: u32) -> f32 {
+        cfg::XP_FIRST_LEVEL * cfg::XP_LEVEL_GROWTH.powi(level as i32 - 1)
+    }
+
+    pub fn progress(&self) -> f32 {
+        (self.current / self.to_next).clamp(0.0, 1.0)
+    }
+}
+
+#[derive(Component)]
+pub struct XpGem {
+    pub value: f32,
+}
+
+// Set once a gem enters the magnet radius; it then homes in on the player
+// even if the player moves away again.
+#[derive(Component)]
+pub struct Attracted;
+
+// Pulls pickups (xp gems, loot) towards the player. Built once per frame; the
+// systems using it only decide what collecting a pickup does.
+pub struct Magnet {
+    player_pos: Vec2,
+    radius: f32,
+    step: f32,
+}
+
+impl Magnet {
+    pub fn new(player_tf: &Transform, stats: &EffectiveStats, time: &Time) -> Self {
+        Magnet {
+            player_pos: player_tf.translation.truncate(),
+            radius: stats.pickup_radius,
+            step: cfg::XP_GEM_SPEED * time.delta_secs(),
+        }
+    }
+
+    // moves the pickup one frame closer; true once it's close enough to collect
+    pub fn pull(&self, commands: &mut Commands, entity: Entity, transform: &mut Transform, attracted: bool) -> bool {
+        let pos = transform.translation.truncate();
+        let dist = pos.distance(self.player_pos);
+        if dist <= cfg::XP_PICKUP_RADIUS {
+            return true;
+        }
+
+        if !attracted {
+            if dist > self.radius {
+                return false;
+            }
+            commands.entity(entity).insert(Attracted);
+        }
+
+        let new_pos = pos.move_towards(self.player_pos, self.step);
+        transform.translation.x = new_pos.x;
+        transform.translation.y = new_pos.y;
+        false
+    }
+}
+
+pub struct ExperiencePlugin;
+
+impl Plugin for ExperiencePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(drop_xp_gem).add_systems(
+            Update,
+            (attract_and_collect_xp_gems, check_level_up)
+                .chain()
+                .run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
+fn drop_xp_gem(event: On<EnemyKilledEvent>, mut commands: Commands, atlas: Res<GlobalTextureAtlas>) {
+    let mut sprite = Sprite::from_atlas_image(
+        atlas.image.clone(),
+        TextureAtlas {
+            layout: atlas.layout.clone(),
+            index: cfg::XP_GEM_SPRITE,
+        },
+    );
+    sprite.color = Color::srgb(0.4, 1.0, 0.5);
+
+    commands.spawn((
+        sprite,
+        Transform::from_xyz(event.position.x, event.position.y, 0.5).with_scale(Vec3::splat(0.75)),
+        XpGem { value: cfg::XP_PER_KILL },
+        DespawnOnExit(GameState::GameOver),
+    ));
+}
+
+fn attract_and_collect_xp_gems(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut player_query: Query<(&Transform, &EffectiveStats, &mut Experience), (With<Player>, Without<Dying>)>,
+    mut gem_query: Query<(Entity, &mut Transform, &XpGem, Has<Attracted>), Without<Player>>,
+) {
+    let Ok((player_tf, stats, mut experience)) = player_query.single_mut() else {
+        return;
+    };
+    let magnet = Magnet::new(player_tf, stats, &time);
+
+    for (entity, mut transform, gem, attracted) in gem_query.iter_mut() {
+        if magnet.pull(&mut commands, entity, &mut transform, attracted) {
+            experience.current += gem.value;
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+// one level per frame; leftover xp carries over and can trigger the next
+// level-up as soon as the game resumes
+fn check_level_up(
+    mut player_query: Query<&mut Experience, With<Player>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let Ok(mut experience) = player_query.single_mut() else {
+        return;
+    };
+    if experience.current < experience.to_next {
+        return;
+    }
+
+    experience.current -= experience.to_next;
+    experience.level += 1;
+    experience.to_next = Experience::required_for(experience.level);
+    info!("Player reached level {}", experience.level);
+    next_state.set(GameState::LevelUp);
+}
@@ -0,0 +1,214 @@
+use bevy::prelude::*;
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::game::assets::GameAssets;
+use crate::game::common::components::characters::health::Health;
+use crate::game::common::components::characters::stats::EffectiveStats;
+use crate::game::config as cfg;
+use crate::game::enemies::affixes::Elite;
+use crate::game::enemies::events::EnemyKilledEvent;
+use crate::game::enemies::registry::{EnemyKind, EnemyRegistry};
+use crate::game::game_state::GameState;
+use crate::game::player::component::Player;
+use crate::game::player::death::Dying;
+use crate::game::player::inventory::{equip_weapon, Inventory};
+use crate::game::player::weapon::WeaponModifiers;
+use crate::game::player::weapon_spec::{WeaponList, WeaponSpec};
+use crate::game::progression::experience::{Attracted, Magnet, XpGem};
+use crate::game::resources::GlobalTextureAtlas;
+use crate::game::rng::{weighted_pick, GameRng, RngStream};
+use crate::game::run::RunStats;
+
+// What an enemy drops, authored per enemy in its RON spec. Everything in
+// `guaranteed` always drops; then, with probability `chance`, `rolls` entries
+// are picked from `entries` by weight. `rolls` of 0, the default when left
+// out, counts as one roll.
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct LootTable {
+    pub guaranteed: Vec<LootEntry>,
+    pub chance: f32,
+    pub rolls: u32,
+    pub entries: Vec<LootEntry>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct LootEntry {
+    pub item: LootItem,
+    #[serde(default = "default_weight")]
+    pub weight: f32,
+    // inclusive (min, max) number of pickups
+    #[serde(default = "default_quantity")]
+    pub quantity: (u32, u32),
+}
+
+fn default_weight() -> f32 {
+    1.0
+}
+
+fn default_quantity() -> (u32, u32) {
+    (1, 1)
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum LootItem {
+    // restores this much health
+    HealthOrb(f32),
+    Currency(u32),
+    // an extra xp gem worth this much
+    Xp(f32),
+    // a random weapon the player doesn't carry yet
+    WeaponChest,
+}
+
+impl LootItem {
+    fn color(&self) -> Color {
+        match self {
+            LootItem::HealthOrb(_) => Color::srgb(1.0, 0.35, 0.45),
+            LootItem::Currency(_) => Color::srgb(1.0, 0.85, 0.2),
+            LootItem::Xp(_) => Color::srgb(0.4, 1.0, 0.5),
+            LootItem::WeaponChest => Color::srgb(0.7, 0.45, 0.2),
+        }
+    }
+}
+
+impl LootTable {
+    // every pickup this table drops for one kill; `bonus` (from elite affixes)
+    // raises the chance and adds a roll per whole point
+    pub fn roll(&self, bonus: f32, rng: &mut impl Rng) -> Vec<LootItem> {
+        let mut drops = Vec::new();
+        for entry in self.guaranteed.iter() {
+            push_quantity(&mut drops, entry, rng);
+        }
+
+        if rng.random::<f32>() >= self.chance + bonus {
+            return drops;
+        }
+        let weighted: Vec<(&LootEntry, f32)> = self.entries.iter().map(|e| (e, e.weight)).collect();
+        for _ in 0..self.rolls.max(1) + bonus.floor() as u32 {
+            let Some(entry) = weighted_pick(&weighted, rng) else { break };
+            push_quantity(&mut drops, entry, rng);
+        }
+        drops
+    }
+}
+
+fn push_quantity(drops: &mut Vec<LootItem>, entry: &LootEntry, rng: &mut impl Rng) {
+    let (min, max) = entry.quantity;
+    let count = rng.random_range(min..=max.max(min));
+    drops.extend(std::iter::repeat_n(entry.item, count as usize));
+}
+
+#[derive(Component)]
+pub struct Pickup(pub LootItem);
+
+pub struct LootPlugin;
+
+impl Plugin for LootPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(drop_loot)
+            .add_systems(Update, attract_and_collect_pickups.run_if(in_state(GameState::InGame)));
+    }
+}
+
+fn drop_loot(
+    event: On<EnemyKilledEvent>,
+    mut commands: Commands,
+    atlas: Res<GlobalTextureAtlas>,
+    registry: Option<Res<EnemyRegistry>>,
+    mut game_rng: ResMut<GameRng>,
+    kind_query: Query<(&EnemyKind, Option<&Elite>)>,
+) {
+    let Some(registry) = registry else { return };
+    let Ok((kind, elite)) = kind_query.get(event.entity) else { return };
+    let bonus = elite.map_or(0.0, |e| e.loot_bonus);
+    let rng = game_rng.stream(RngStream::Loot);
+
+    for item in registry.get(*kind).loot.roll(bonus, rng) {
+        // scatter the pile a little so stacked pickups stay readable
+        let offset = Vec2::new(rng.random_range(-12.0..12.0), rng.random_range(-12.0..12.0));
+        let pos = event.position.truncate() + offset;
+        let mut sprite = Sprite::from_atlas_image(
+            atlas.image.clone(),
+            TextureAtlas {
+                layout: atlas.layout.clone(),
+                index: cfg::LOOT_SPRITE,
+            },
+        );
+        sprite.color = item.color();
+
+        // xp drops are ordinary gems so they level the player the usual way
+        if let LootItem::Xp(value) = item {
+            commands.spawn((
+                sprite,
+                Transform::from_xyz(pos.x, pos.y, 0.5).with_scale(Vec3::splat(0.75)),
+                XpGem { value },
+                DespawnOnExit(GameState::GameOver),
+            ));
+            continue;
+        }
+        commands.spawn((
+            sprite,
+            Transform::from_xyz(pos.x, pos.y, 0.5),
+            Pickup(item),
+            DespawnOnExit(GameState::GameOver),
+        ));
+    }
+}
+
+// pulled in by the same `Magnet` as xp gems; only the effect differs
+fn attract_and_collect_pickups(
+    mut commands: Commands,
+    time: Res<Time>,
+    game_assets: Res<GameAssets>,
+    weapon_lists: Res<Assets<WeaponList>>,
+    atlas: Res<GlobalTextureAtlas>,
+    mut run_stats: ResMut<RunStats>,
+    mut game_rng: ResMut<GameRng>,
+    mut player_query: Query<
+        (&Transform, &EffectiveStats, &mut Health, &mut Inventory, &WeaponModifiers),
+        (With<Player>, Without<Dying>),
+    >,
+    mut pickup_query: Query<(Entity, &mut Transform, &Pickup, Has<Attracted>), Without<Player>>,
+    held_query: Query<&WeaponSpec>,
+) {
+    let Ok((player_tf, stats, mut health, mut inventory, upgrades)) = player_query.single_mut() else {
+        return;
+    };
+    let magnet = Magnet::new(player_tf, stats, &time);
+
+    for (entity, mut transform, pickup, attracted) in pickup_query.iter_mut() {
+        if !magnet.pull(&mut commands, entity, &mut transform, attracted) {
+            continue;
+        }
+        match pickup.0 {
+            LootItem::HealthOrb(amount) => health.heal(amount),
+            LootItem::Currency(value) => run_stats.currency += value,
+            LootItem::Xp(_) => {}
+            LootItem::WeaponChest => {
+                let held: Vec<&str> = inventory
+                    .slots()
+                    .iter()
+                    .filter_map(|slot| held_query.get(slot.weapon).ok())
+                    .map(|spec| spec.id.as_str())
+                    .collect();
+                let candidates: Vec<&WeaponSpec> = weapon_lists
+                    .get(&game_assets.weapon_list)
+                    .map(|list| list.weapons.iter().filter(|w| !held.contains(&w.id.as_str())).collect())
+                    .unwrap_or_default();
+
+                // nothing new to give or no room for it: the chest pays out instead
+                if inventory.is_full() || candidates.is_empty() {
+                    info!("Weapon chest opened with no free slot, got {} currency", cfg::CHEST_FALLBACK_CURRENCY);
+                    run_stats.currency += cfg::CHEST_FALLBACK_CURRENCY;
+                } else {
+                    let rng = game_rng.stream(RngStream::Loot);
+                    let spec = candidates[rng.random_range(0..candidates.len())];
+                    equip_weapon(&mut commands, &atlas, &mut inventory, upgrades, spec);
+                }
+            }
+        }
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,3 @@
+pub mod experience;
+pub mod loot;
+pub mod upgrades;
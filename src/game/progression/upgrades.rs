@@ -0,0 +1,133 @@
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use bevy_common_assets::ron::RonAssetPlugin;
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::game::common::components::characters::health::Health;
+use crate::game::common::components::characters::stats::{ModifierSource, StatModifier, StatModifiers};
+use crate::game::player::component::Player;
+use crate::game::player::weapon::{Weapon, WeaponModifier, WeaponModifiers};
+use crate::game::rng::weighted_pick;
+
+// Pool of level-up upgrades, loaded from `assets/upgrades/upgrades.ron`.
+#[derive(Deserialize, Asset, TypePath)]
+pub struct UpgradeList {
+    pub upgrades: Vec<UpgradeSpec>,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct UpgradeSpec {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub rarity: Rarity,
+    // relative chance to be offered, before the rarity multiplier
+    pub weight: f32,
+    pub max_stacks: u32,
+    pub effects: Vec<UpgradeEffect>,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rarity {
+    Common,
+    Uncommon,
+    Rare,
+    Epic,
+}
+
+impl Rarity {
+    pub fn weight_multiplier(self) -> f32 {
+        match self {
+            Rarity::Common => 1.0,
+            Rarity::Uncommon => 0.6,
+            Rarity::Rare => 0.3,
+            Rarity::Epic => 0.1,
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            Rarity::Common => Color::srgb(0.8, 0.8, 0.8),
+            Rarity::Uncommon => Color::srgb(0.3, 0.9, 0.4),
+            Rarity::Rare => Color::srgb(0.3, 0.6, 1.0),
+            Rarity::Epic => Color::srgb(0.8, 0.4, 1.0),
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+pub enum UpgradeEffect {
+    Stat(StatModifier),
+    Weapon(WeaponModifier),
+    Heal(f32),
+}
+
+// How many times the player has taken each upgrade this run.
+#[derive(Component, Default)]
+pub struct AcquiredUpgrades(pub HashMap<String, u32>);
+
+impl AcquiredUpgrades {
+    pub fn stacks(&self, id: &str) -> u32 {
+        self.0.get(id).copied().unwrap_or(0)
+    }
+}
+
+pub struct UpgradesPlugin;
+
+impl Plugin for UpgradesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(RonAssetPlugin::<UpgradeList>::new(&["upgrades.ron"]));
+    }
+}
+
+impl UpgradeList {
+    // Weighted pick of up to `count` distinct upgrades that haven't hit their
+    // stack cap. Returns indices into `upgrades`.
+    pub fn roll(&self, acquired: &AcquiredUpgrades, count: usize, rng: &mut impl Rng) -> Vec<usize> {
+        let mut candidates: Vec<(usize, f32)> = self
+            .upgrades
+            .iter()
+            .enumerate()
+            .filter(|(_, u)| acquired.stacks(&u.id) < u.max_stacks)
+            .map(|(i, u)| (i, u.weight * u.rarity.weight_multiplier()))
+            .collect();
+
+        let mut picked = Vec::with_capacity(count);
+        while picked.len() < count {
+            let Some(&index) = weighted_pick(&candidates, rng) else { break };
+            picked.push(index);
+            candidates.retain(|(i, _)| *i != index);
+        }
+        picked
+    }
+}
+
+impl UpgradeSpec {
+    pub fn apply(
+        &self,
+        acquired: &mut AcquiredUpgrades,
+        stat_modifiers: &mut StatModifiers,
+        health: &mut Health,
+        weapon_upgrades: &mut WeaponModifiers,
+        weapons: &mut Query<&mut WeaponModifiers, (With<Weapon>, Without<Player>)>,
+    ) {
+        for effect in &self.effects {
+            match effect {
+                UpgradeEffect::Stat(modifier) => {
+                    stat_modifiers.add(ModifierSource::Upgrade(self.id.clone()), *modifier);
+                }
+                UpgradeEffect::Weapon(modifier) => {
+                    // kept on the player too, so weapons equipped later get it
+                    weapon_upgrades.stack(modifier);
+                    for mut weapon in weapons.iter_mut() {
+                        weapon.stack(modifier);
+                    }
+                }
+                UpgradeEffect::Heal(amount) => health.heal(*amount),
+            }
+        }
+        *acquired.0.entry(self.id.clone()).or_insert(0) += 1;
+        info!("Took upgrade {} ({}/{})", self.name, acquired.stacks(&self.id), self.max_stacks);
+    }
+}
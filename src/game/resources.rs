@@ -52,7 +52,7 @@ pub fn load_assets(
     );
 
     handle.layout = layouts.add(layout);
-    next_state.set(GameState::InGame);
+    next_state.set(GameState::Loading);
 }
 
 fn update_cursor_position(
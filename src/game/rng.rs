@@ -0,0 +1,135 @@
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+use crate::game::game_state::GameState;
+
+// Independent random streams. Each subsystem draws from its own stream so
+// e.g. firing an extra shot doesn't shift which enemies spawn next.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RngStream {
+    Weapons,
+    EnemySpawns,
+    Upgrades,
+    Terrain,
+    Loot,
+}
+
+// Seeded source of all gameplay randomness. The same seed (and the same
+// inputs) replays the same run; pass one with `--seed <number>`.
+#[derive(Resource)]
+pub struct GameRng {
+    seed: u64,
+    streams: HashMap<RngStream, StdRng>,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        GameRng { seed, streams: HashMap::default() }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    // streams are created on first use from the run seed and the stream id,
+    // so the order subsystems first ask for them doesn't matter
+    pub fn stream(&mut self, stream: RngStream) -> &mut StdRng {
+        let seed = self.seed;
+        self.streams
+            .entry(stream)
+            .or_insert_with(|| StdRng::seed_from_u64(stream_seed(seed, stream)))
+    }
+
+    // rewind every stream to the start of the run
+    pub fn reset(&mut self) {
+        self.streams.clear();
+    }
+}
+
+fn stream_seed(seed: u64, stream: RngStream) -> u64 {
+    // golden ratio increment keeps neighbouring stream seeds far apart
+    seed.wrapping_add((stream as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15))
+}
+
+pub struct RngPlugin;
+
+impl Plugin for RngPlugin {
+    fn build(&self, app: &mut App) {
+        let seed = seed_from_args().unwrap_or_else(rand::random::<u64>);
+        info!("Run seed: {}", seed);
+
+        app.insert_resource(GameRng::new(seed)).add_systems(
+            OnTransition { exited: GameState::GameOver, entered: GameState::InGame },
+            reset_streams,
+        );
+    }
+}
+
+// `--seed 1234` or `--seed=1234`
+fn seed_from_args() -> Option<u64> {
+    let args: Vec<String> = std::env::args().collect();
+    let value = args.iter().enumerate().find_map(|(i, arg)| match arg.strip_prefix("--seed") {
+        Some("") => args.get(i + 1).cloned(),
+        Some(rest) => rest.strip_prefix('=').map(String::from),
+        None => None,
+    })?;
+
+    match value.parse() {
+        Ok(seed) => Some(seed),
+        Err(_) => {
+            warn!("Ignoring invalid --seed `{}`, using a random one", value);
+            None
+        }
+    }
+}
+
+// Picks one item with probability proportional to its weight. Items weighing
+// zero or less are never picked; `None` when nothing can be.
+pub fn weighted_pick<'a, T>(items: &'a [(T, f32)], rng: &mut impl Rng) -> Option<&'a T> {
+    let total: f32 = items.iter().map(|(_, weight)| weight.max(0.0)).sum();
+    if total <= 0.0 {
+        return None;
+    }
+    let mut pick = rng.random_range(0.0..total);
+    let mut last = None;
+    for (item, weight) in items.iter().filter(|(_, weight)| *weight > 0.0) {
+        if pick < *weight {
+            return Some(item);
+        }
+        pick -= weight;
+        last = Some(item);
+    }
+    // rounding can leave `pick` a hair past the last weight
+    last
+}
+
+// a restart replays the same seed from the beginning
+fn reset_streams(mut rng: ResMut<GameRng>) {
+    rng.reset();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weighted_pick_skips_non_positive_weights() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let items = [("zero", 0.0), ("negative", -1.0), ("only", 2.0), ("nan", f32::NAN)];
+        for _ in 0..1000 {
+            assert_eq!(weighted_pick(&items, &mut rng), Some(&"only"));
+        }
+        assert_eq!(weighted_pick(&[("zero", 0.0)], &mut rng), None);
+        assert_eq!(weighted_pick::<&str>(&[], &mut rng), None);
+    }
+
+    #[test]
+    fn weighted_pick_follows_the_weights() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let items = [(0, 1.0), (1, 3.0)];
+        let heavy = (0..10_000).filter(|_| weighted_pick(&items, &mut rng) == Some(&1)).count();
+        assert!((7_000..8_000).contains(&heavy), "picked the heavy item {heavy} times");
+    }
+}
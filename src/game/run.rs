@@ -0,0 +1,47 @@
+use bevy::prelude::*;
+use bevy::time::Stopwatch;
+
+use crate::game::game_state::GameState;
+use crate::game::player::component::Player;
+use crate::game::player::death::Dying;
+
+// Summary of the current run, shown on the game over screen.
+#[derive(Resource, Default)]
+pub struct RunStats {
+    pub time_survived: Stopwatch,
+    pub kills: u32,
+    pub damage_dealt: f32,
+    pub bosses_defeated: u32,
+    pub currency: u32,
+    // set when a boss that ends the run is beaten
+    pub victory: bool,
+}
+
+pub struct RunPlugin;
+
+impl Plugin for RunPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RunStats>()
+            .add_systems(Update, tick_run_timer.run_if(in_state(GameState::InGame)))
+            .add_systems(
+                OnTransition { exited: GameState::GameOver, entered: GameState::InGame },
+                reset_run_stats,
+            );
+    }
+}
+
+// the clock stops as soon as the player starts dying
+fn tick_run_timer(
+    time: Res<Time>,
+    mut stats: ResMut<RunStats>,
+    player_query: Query<(), (With<Player>, Without<Dying>)>,
+) {
+    if player_query.is_empty() {
+        return;
+    }
+    stats.time_survived.tick(time.delta());
+}
+
+fn reset_run_stats(mut stats: ResMut<RunStats>) {
+    *stats = RunStats::default();
+}
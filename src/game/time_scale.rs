@@ -0,0 +1,102 @@
+use bevy::prelude::*;
+
+use crate::game::game_state::GameState;
+
+// Gameplay reads virtual time (`Res<Time>` in Update / FixedUpdate), so
+// lifetimes, cooldowns and spawn timers stop while the game is paused and
+// stretch under slow motion. The effects below count down in real seconds so a
+// hit-stop doesn't freeze its own timer.
+#[derive(Resource)]
+pub struct TimeScale {
+    slow_motion: f32,
+    slow_motion_left: f32,
+    hit_stop_left: f32,
+}
+
+impl Default for TimeScale {
+    fn default() -> Self {
+        TimeScale {
+            slow_motion: 1.0,
+            slow_motion_left: 0.0,
+            hit_stop_left: 0.0,
+        }
+    }
+}
+
+impl TimeScale {
+    // run the game at `scale` speed for `secs` real seconds; overlapping
+    // requests keep the slowest scale and the longest duration
+    pub fn slow_motion(&mut self, scale: f32, secs: f32) {
+        let scale = scale.clamp(0.0, 1.0);
+        if self.slow_motion_left <= 0.0 || scale < self.slow_motion {
+            self.slow_motion = scale;
+        }
+        self.slow_motion_left = self.slow_motion_left.max(secs);
+    }
+
+    // freeze gameplay for `secs` real seconds
+    pub fn hit_stop(&mut self, secs: f32) {
+        self.hit_stop_left = self.hit_stop_left.max(secs);
+    }
+
+    pub fn speed(&self) -> f32 {
+        if self.hit_stop_left > 0.0 {
+            0.0
+        } else if self.slow_motion_left > 0.0 {
+            self.slow_motion
+        } else {
+            1.0
+        }
+    }
+}
+
+pub struct TimeScalePlugin;
+
+impl Plugin for TimeScalePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TimeScale>()
+            .add_systems(PreUpdate, apply_time_scale)
+            // menus on top of a run freeze it; pausing virtual time (rather than
+            // only gating systems on the state) also stops on_timer conditions
+            .add_systems(OnEnter(GameState::Paused), pause_virtual_time)
+            .add_systems(OnExit(GameState::Paused), unpause_virtual_time)
+            .add_systems(OnEnter(GameState::LevelUp), pause_virtual_time)
+            .add_systems(OnExit(GameState::LevelUp), unpause_virtual_time)
+            .add_systems(
+                OnTransition { exited: GameState::GameOver, entered: GameState::InGame },
+                reset_time_scale,
+            );
+    }
+}
+
+fn apply_time_scale(
+    real: Res<Time<Real>>,
+    mut scale: ResMut<TimeScale>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+) {
+    let dt = real.delta_secs();
+    if scale.hit_stop_left > 0.0 {
+        scale.hit_stop_left -= dt;
+    }
+    if scale.slow_motion_left > 0.0 {
+        scale.slow_motion_left -= dt;
+    }
+
+    let speed = scale.speed();
+    if virtual_time.relative_speed() != speed {
+        virtual_time.set_relative_speed(speed);
+    }
+}
+
+fn pause_virtual_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+fn unpause_virtual_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+fn reset_time_scale(mut scale: ResMut<TimeScale>, mut time: ResMut<Time<Virtual>>) {
+    *scale = TimeScale::default();
+    time.set_relative_speed(1.0);
+}
@@ -0,0 +1,89 @@
+use bevy::prelude::*;
+
+use crate::game::common::components::characters::health::Health;
+use crate::game::enemies::boss::Boss;
+
+#[derive(Component)]
+struct BossBar;
+
+#[derive(Component)]
+struct BossBarFill;
+
+#[derive(Component)]
+struct BossNameText;
+
+pub struct BossBarPlugin;
+
+impl Plugin for BossBarPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_boss_bar)
+            .add_systems(Update, update_boss_bar);
+    }
+}
+
+// spans the bottom of the screen and stays hidden while no boss is alive
+fn spawn_boss_bar(mut commands: Commands) {
+    commands.spawn((
+        BossBar,
+        Visibility::Hidden,
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(16.0),
+            left: Val::Percent(10.0),
+            width: Val::Percent(80.0),
+            height: Val::Px(20.0),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+        children![
+            (
+                BossBarFill,
+                Node {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                BackgroundColor(Color::srgb(0.8, 0.1, 0.1)),
+            ),
+            (
+                BossNameText,
+                Text::new(""),
+                TextFont { font_size: 14.0, ..default() },
+                TextColor(Color::WHITE),
+                Node {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(8.0),
+                    ..default()
+                },
+            ),
+        ],
+    ));
+}
+
+fn update_boss_bar(
+    boss_query: Query<(&Boss, &Health)>,
+    mut bar_query: Query<&mut Visibility, With<BossBar>>,
+    mut fill_query: Query<&mut Node, With<BossBarFill>>,
+    mut text_query: Query<&mut Text, With<BossNameText>>,
+) {
+    // with several bosses alive the bar follows the first one found
+    let boss = boss_query.iter().next();
+
+    if let Ok(mut visibility) = bar_query.single_mut() {
+        let wanted = if boss.is_some() { Visibility::Inherited } else { Visibility::Hidden };
+        if *visibility != wanted {
+            *visibility = wanted;
+        }
+    }
+    let Some((boss, health)) = boss else { return };
+
+    if let Ok(mut node) = fill_query.single_mut() {
+        node.width = Val::Percent((health.current / health.max.max(1.0)).clamp(0.0, 1.0) * 100.0);
+    }
+    if let Ok(mut text) = text_query.single_mut() {
+        let label = format!("{} - phase {}", boss.name, boss.phase + 1);
+        if text.0 != label {
+            text.0 = label;
+        }
+    }
+}
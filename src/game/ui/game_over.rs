@@ -0,0 +1,73 @@
+use bevy::prelude::*;
+
+use crate::game::game_state::GameState;
+use crate::game::input::actions::{Action, ActionState};
+use crate::game::input::bindings::InputMap;
+use crate::game::run::RunStats;
+use crate::game::rng::GameRng;
+
+pub struct GameOverPlugin;
+
+impl Plugin for GameOverPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::GameOver), spawn_game_over_screen)
+            .add_systems(Update, restart_on_input.run_if(in_state(GameState::GameOver)));
+    }
+}
+
+fn spawn_game_over_screen(
+    mut commands: Commands,
+    stats: Res<RunStats>,
+    input_map: Res<InputMap>,
+    rng: Res<GameRng>,
+) {
+    let secs = stats.time_survived.elapsed_secs() as u32;
+    let restart_key = input_map
+        .inputs(Action::Restart)
+        .first()
+        .map(|input| input.label())
+        .unwrap_or_else(|| String::from("Restart"));
+
+    commands.spawn((
+        DespawnOnExit(GameState::GameOver),
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(12.0),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.7)),
+        children![
+            (
+                Text::new(if stats.victory { "VICTORY" } else { "GAME OVER" }),
+                TextFont { font_size: 64.0, ..default() },
+                TextColor(if stats.victory { Color::srgb(0.3, 0.9, 0.3) } else { Color::srgb(0.9, 0.2, 0.2) }),
+            ),
+            summary_line(format!("Time survived: {:02}:{:02}", secs / 60, secs % 60)),
+            summary_line(format!("Kills: {}", stats.kills)),
+            summary_line(format!("Bosses defeated: {}", stats.bosses_defeated)),
+            summary_line(format!("Gold: {}", stats.currency)),
+            summary_line(format!("Damage dealt: {:.0}", stats.damage_dealt)),
+            summary_line(format!("Seed: {}", rng.seed())),
+            summary_line(format!("Press {restart_key} to restart")),
+        ],
+    ));
+}
+
+fn summary_line(text: String) -> impl Bundle {
+    (
+        Text::new(text),
+        TextFont { font_size: 28.0, ..default() },
+        TextColor(Color::WHITE),
+    )
+}
+
+fn restart_on_input(actions: Res<ActionState>, mut next_state: ResMut<NextState<GameState>>) {
+    if actions.just_pressed(Action::Restart) {
+        info!("Restarting run");
+        next_state.set(GameState::InGame);
+    }
+}
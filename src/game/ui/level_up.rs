@@ -0,0 +1,198 @@
+use bevy::prelude::*;
+
+use crate::game::rng::{GameRng, RngStream};
+use crate::game::assets::GameAssets;
+use crate::game::common::components::characters::health::Health;
+use crate::game::common::components::characters::stats::StatModifiers;
+use crate::game::config as cfg;
+use crate::game::game_state::GameState;
+use crate::game::input::actions::{Action, ActionState};
+use crate::game::player::component::Player;
+use crate::game::player::weapon::{Weapon, WeaponModifiers};
+use crate::game::progression::experience::Experience;
+use crate::game::progression::upgrades::{AcquiredUpgrades, UpgradeList};
+
+// The upgrades on offer (indices into the `UpgradeList`) and which card is
+// highlighted.
+#[derive(Resource, Default)]
+struct LevelUpChoices {
+    offered: Vec<usize>,
+    selected: usize,
+}
+
+#[derive(Component)]
+struct UpgradeCard(usize);
+
+pub struct LevelUpPlugin;
+
+impl Plugin for LevelUpPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LevelUpChoices>()
+            .add_systems(OnEnter(GameState::LevelUp), open_level_up_screen)
+            .add_systems(
+                Update,
+                (navigate_cards, highlight_cards, pick_upgrade)
+                    .chain()
+                    .run_if(in_state(GameState::LevelUp)),
+            );
+    }
+}
+
+fn open_level_up_screen(
+    mut commands: Commands,
+    mut choices: ResMut<LevelUpChoices>,
+    game_assets: Res<GameAssets>,
+    upgrade_lists: Res<Assets<UpgradeList>>,
+    player_query: Query<(&AcquiredUpgrades, &Experience), With<Player>>,
+    mut game_rng: ResMut<GameRng>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let (Some(list), Ok((acquired, experience))) =
+        (upgrade_lists.get(&game_assets.upgrade_list), player_query.single())
+    else {
+        next_state.set(GameState::InGame);
+        return;
+    };
+
+    let rng = game_rng.stream(RngStream::Upgrades);
+    choices.offered = list.roll(acquired, cfg::LEVEL_UP_CHOICES, rng);
+    choices.selected = 0;
+
+    // every upgrade is maxed out: nothing to offer, carry on
+    if choices.offered.is_empty() {
+        next_state.set(GameState::InGame);
+        return;
+    }
+
+    let root = commands
+        .spawn((
+            DespawnOnExit(GameState::LevelUp),
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(24.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+        ))
+        .id();
+
+    commands.entity(root).with_children(|parent| {
+        parent.spawn((
+            Text::new(format!("LEVEL {}", experience.level)),
+            TextFont { font_size: 48.0, ..default() },
+            TextColor(Color::srgb(1.0, 0.85, 0.3)),
+        ));
+
+        parent
+            .spawn(Node {
+                flex_direction: FlexDirection::Row,
+                column_gap: Val::Px(16.0),
+                ..default()
+            })
+            .with_children(|row| {
+                for (slot, &index) in choices.offered.iter().enumerate() {
+                    let upgrade = &list.upgrades[index];
+                    let stacks = acquired.stacks(&upgrade.id);
+                    row.spawn((
+                        Button,
+                        UpgradeCard(slot),
+                        Node {
+                            width: Val::Px(220.0),
+                            min_height: Val::Px(140.0),
+                            flex_direction: FlexDirection::Column,
+                            padding: UiRect::all(Val::Px(12.0)),
+                            row_gap: Val::Px(8.0),
+                            border: UiRect::all(Val::Px(3.0)),
+                            ..default()
+                        },
+                        BorderColor::all(upgrade.rarity.color()),
+                        BackgroundColor(Color::srgba(0.1, 0.1, 0.15, 0.95)),
+                        children![
+                            (
+                                Text::new(upgrade.name.clone()),
+                                TextFont { font_size: 26.0, ..default() },
+                                TextColor(upgrade.rarity.color()),
+                            ),
+                            (
+                                Text::new(format!("{:?}  {}/{}", upgrade.rarity, stacks + 1, upgrade.max_stacks)),
+                                TextFont { font_size: 16.0, ..default() },
+                                TextColor(Color::srgb(0.6, 0.6, 0.6)),
+                            ),
+                            (
+                                Text::new(upgrade.description.clone()),
+                                TextFont { font_size: 20.0, ..default() },
+                                TextColor(Color::WHITE),
+                            ),
+                        ],
+                    ));
+                }
+            });
+    });
+}
+
+// keyboard / d-pad moves the highlight, hovering with the mouse does too
+fn navigate_cards(
+    actions: Res<ActionState>,
+    mut choices: ResMut<LevelUpChoices>,
+    cards: Query<(&UpgradeCard, &Interaction), Changed<Interaction>>,
+) {
+    let count = choices.offered.len();
+    if count == 0 {
+        return;
+    }
+
+    if actions.just_pressed(Action::MoveRight) {
+        choices.selected = (choices.selected + 1) % count;
+    }
+    if actions.just_pressed(Action::MoveLeft) {
+        choices.selected = (choices.selected + count - 1) % count;
+    }
+
+    for (card, interaction) in cards.iter() {
+        if *interaction != Interaction::None {
+            choices.selected = card.0;
+        }
+    }
+}
+
+fn highlight_cards(choices: Res<LevelUpChoices>, mut cards: Query<(&UpgradeCard, &mut BackgroundColor)>) {
+    if !choices.is_changed() {
+        return;
+    }
+    for (card, mut background) in cards.iter_mut() {
+        background.0 = if card.0 == choices.selected {
+            Color::srgba(0.25, 0.25, 0.35, 0.95)
+        } else {
+            Color::srgba(0.1, 0.1, 0.15, 0.95)
+        };
+    }
+}
+
+fn pick_upgrade(
+    actions: Res<ActionState>,
+    choices: Res<LevelUpChoices>,
+    cards: Query<&Interaction, (Changed<Interaction>, With<UpgradeCard>)>,
+    game_assets: Res<GameAssets>,
+    upgrade_lists: Res<Assets<UpgradeList>>,
+    mut player_query: Query<(&mut AcquiredUpgrades, &mut StatModifiers, &mut Health, &mut WeaponModifiers), With<Player>>,
+    mut weapons: Query<&mut WeaponModifiers, (With<Weapon>, Without<Player>)>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let clicked = cards.iter().any(|i| *i == Interaction::Pressed);
+    if !clicked && !actions.just_pressed(Action::Confirm) {
+        return;
+    }
+
+    let Some(&index) = choices.offered.get(choices.selected) else { return };
+    let Some(list) = upgrade_lists.get(&game_assets.upgrade_list) else { return };
+    let Ok((mut acquired, mut stat_modifiers, mut health, mut weapon_upgrades)) = player_query.single_mut() else {
+        return;
+    };
+
+    list.upgrades[index].apply(&mut acquired, &mut stat_modifiers, &mut health, &mut weapon_upgrades, &mut weapons);
+    next_state.set(GameState::InGame);
+}
@@ -1 +1,5 @@
+pub mod boss_bar;
 pub mod fps;
+pub mod game_over;
+pub mod level_up;
+pub mod xp_bar;
@@ -0,0 +1,87 @@
+use bevy::prelude::*;
+
+use crate::game::player::component::Player;
+use crate::game::progression::experience::Experience;
+use crate::game::rng::GameRng;
+
+#[derive(Component)]
+struct XpBarFill;
+
+#[derive(Component)]
+struct LevelText;
+
+pub struct XpBarPlugin;
+
+impl Plugin for XpBarPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, spawn_xp_bar)
+            .add_systems(Update, update_xp_bar);
+    }
+}
+
+// the run seed sits on the left of the bar so a run can be replayed with --seed
+fn spawn_xp_bar(mut commands: Commands, rng: Res<GameRng>) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(0.0),
+            left: Val::Px(0.0),
+            width: Val::Percent(100.0),
+            height: Val::Px(14.0),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+        children![
+            (
+                XpBarFill,
+                Node {
+                    width: Val::Percent(0.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                BackgroundColor(Color::srgb(0.3, 0.8, 1.0)),
+            ),
+            (
+                Text::new(format!("Seed {}", rng.seed())),
+                TextFont { font_size: 12.0, ..default() },
+                TextColor(Color::WHITE),
+                Node {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(6.0),
+                    ..default()
+                },
+            ),
+            (
+                LevelText,
+                Text::new(""),
+                TextFont { font_size: 12.0, ..default() },
+                TextColor(Color::WHITE),
+                Node {
+                    position_type: PositionType::Absolute,
+                    right: Val::Px(6.0),
+                    ..default()
+                },
+            ),
+        ],
+    ));
+}
+
+fn update_xp_bar(
+    player_query: Query<&Experience, With<Player>>,
+    mut fill_query: Query<&mut Node, With<XpBarFill>>,
+    mut text_query: Query<&mut Text, With<LevelText>>,
+) {
+    let (progress, label) = match player_query.single() {
+        Ok(xp) => (xp.progress(), format!("Lv {}", xp.level)),
+        Err(_) => (0.0, String::new()),
+    };
+
+    if let Ok(mut node) = fill_query.single_mut() {
+        node.width = Val::Percent(progress * 100.0);
+    }
+    if let Ok(mut text) = text_query.single_mut() {
+        if text.0 != label {
+            text.0 = label;
+        }
+    }
+}